@@ -1,10 +1,42 @@
+use anyhow::{anyhow, Result};
+use concordium_base::contracts_common::{schema::Type, Cursor};
+use serde_json::Value as SerdeValue;
 use std::fmt::Display;
 use wasm_bindgen::prelude::*;
 
-use crate::types::JsonString;
+use crate::types::{HexString, JsonString};
 
 pub type JsResult<T = JsonString> = Result<T, JsError>;
 
 pub fn to_js_error(error: impl Display) -> JsError {
     JsError::new(&format!("{}", error))
 }
+
+/// Serialize a JSON value according to the given schema type, returning the
+/// hex-encoded bytes.
+pub fn serialize_type_value(
+    raw_value: JsonString,
+    value_type: &Type,
+    verbose_error_message: bool,
+) -> Result<HexString> {
+    let value: SerdeValue = serde_json::from_str(&raw_value)?;
+
+    let buf = value_type
+        .serial_value(&value)
+        .map_err(|e| anyhow!("{}", e.display(verbose_error_message)))?;
+    Ok(hex::encode(buf))
+}
+
+/// Deserialize hex-encoded bytes according to the given schema type, returning
+/// the value as JSON.
+pub fn deserialize_type_value(
+    serialized_value: HexString,
+    value_type: &Type,
+    verbose_error_message: bool,
+) -> Result<JsonString> {
+    let mut cursor = Cursor::new(hex::decode(serialized_value)?);
+    match value_type.to_json(&mut cursor) {
+        Ok(v) => Ok(serde_json::to_string(&v)?),
+        Err(e) => Err(anyhow!("{}", e.display(verbose_error_message))),
+    }
+}
@@ -2,9 +2,11 @@ use anyhow::{anyhow, Result};
 use concordium_base::contracts_common::{
     from_bytes,
     schema::{ModuleV0, Type, VersionedModuleSchema},
-    Cursor,
 };
-use concordium_rust_bindings_common::types::{HexString, JsonString};
+use concordium_rust_bindings_common::{
+    helpers::{deserialize_type_value, serialize_type_value},
+    types::{HexString, JsonString},
+};
 use serde_json::{to_string, Value as SerdeValue};
 
 /// Given the bytes of a contract's state, deserialize them to a json object,
@@ -154,20 +156,7 @@ pub fn serialize_type_value_aux(
     verbose_error_message: bool,
 ) -> Result<HexString> {
     let parameter_type: Type = from_bytes(&hex::decode(schema)?)?;
-    serialize_type_value(parameters, parameter_type, verbose_error_message)
-}
-
-fn serialize_type_value(
-    raw_value: JsonString,
-    value_type: Type,
-    verbose_error_message: bool,
-) -> Result<HexString> {
-    let value: SerdeValue = serde_json::from_str(&raw_value)?;
-
-    let buf = value_type
-        .serial_value(&value)
-        .map_err(|e| anyhow!("{}", e.display(verbose_error_message)))?;
-    Ok(hex::encode(buf))
+    serialize_type_value(parameters, &parameter_type, verbose_error_message)
 }
 
 pub fn deserialize_type_value_aux(
@@ -179,18 +168,6 @@ pub fn deserialize_type_value_aux(
     deserialize_type_value(serialized_value, &value_type, verbose_error_message)
 }
 
-fn deserialize_type_value(
-    serialized_value: HexString,
-    value_type: &Type,
-    verbose_error_message: bool,
-) -> Result<JsonString> {
-    let mut cursor = Cursor::new(hex::decode(serialized_value)?);
-    match value_type.to_json(&mut cursor) {
-        Ok(v) => Ok(to_string(&v)?),
-        Err(e) => Err(anyhow!("{}", e.display(verbose_error_message))),
-    }
-}
-
 pub fn display_type_schema_template_aux(schema: HexString) -> Result<JsonString> {
     let value_type: Type = from_bytes(&hex::decode(schema)?)?;
     let v = value_type.to_json_template();
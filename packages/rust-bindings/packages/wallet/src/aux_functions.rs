@@ -1,36 +1,83 @@
-use anyhow::{anyhow, bail, Context, Result};
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload as AeadPayload},
+    Aes256Gcm, Nonce,
+};
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use bip39::{Language, Mnemonic};
+use chrono::Datelike;
 use concordium_base::{
-    base::{BakerAggregationSignKey, BakerElectionSignKey, BakerKeyPairs, BakerSignatureSignKey},
+    aggregate_sig,
+    base::{
+        AccountThreshold, AggregateSigPairing, BakerAggregationSignKey, BakerAggregationVerifyKey,
+        BakerElectionSignKey, BakerElectionVerifyKey, BakerId, BakerKeyPairs,
+        BakerSignatureSignKey, BakerSignatureVerifyKey, CredentialRegistrationID, Energy,
+    },
     cis4_types::IssuerKey,
     common::{
-        types::{KeyIndex, KeyPair, TransactionTime},
+        cbor,
+        encryption::{self, EncryptedData, Password},
+        types::{Amount, CredentialIndex, KeyIndex, KeyPair, Signature, TransactionTime},
+        upward::Upward,
         *,
     },
-    contracts_common::ContractAddress,
-    curve_arithmetic::Pairing,
-    ed25519::SigningKey,
+    contracts_common::{self, schema::Type, ContractAddress},
+    curve_arithmetic::{Curve, Pairing},
+    ed25519::{PublicKey, SigningKey},
+    eddsa_ed25519::verify_dlog_ed25519,
+    elgamal::{self, BabyStepGiantStep},
+    encrypted_transfers::{
+        self,
+        types::{AggregatedDecryptedAmount, EncryptedAmount},
+    },
     id::{
         account_holder::{create_credential, create_unsigned_credential},
+        chain::{verify_cdi, CdiVerificationError},
         constants::{self, ArCurve, AttributeKind},
         dodis_yampolskiy_prf as prf,
-        id_proof_types::{Proof, ProofVersion, Statement, StatementWithContext},
-        pedersen_commitment::{Randomness as PedersenRandomness, Value as PedersenValue},
+        id_proof_types::{
+            AtomicStatement, Proof, ProofVersion, RevealAttributeStatement, Statement,
+            StatementWithContext,
+        },
+        identity_provider,
+        pedersen_commitment::{
+            Commitment as PedersenCommitment, CommitmentKey as PedersenKey,
+            Randomness as PedersenRandomness, Value as PedersenValue,
+        },
         types::*,
+        utils,
     },
-    ps_sig::SigRetrievalRandomness,
-    transactions::{ConfigureBakerKeysPayload, Payload},
+    protocol_level_tokens::{TokenOperation, TokenOperationsPayload},
+    ps_sig::{self, SigRetrievalRandomness},
+    random_oracle::RandomOracle,
+    transactions::{cost, ConfigureBakerKeysPayload, Payload},
     web3id::{
+        did::{self, Network},
+        v1::{
+            anchor::{
+                IdentityCredentialType, IdentityProviderDid, LabeledContextProperty,
+                RequestedStatement, RequestedSubjectClaims, VerifiablePresentationV1,
+                VerificationRequest,
+            },
+            AtomicStatementV1, CredentialV1, CredentialVerificationMaterial,
+            OwnedCredentialProofPrivateInputs, PresentationV1, RequestV1, SubjectClaims,
+        },
         CredentialHolderId, OwnedCommitmentInputs, Request, SignedCommitments, Web3IdAttribute,
         Web3IdSigner,
     },
 };
-use concordium_rust_bindings_common::types::{HexString, JsonString};
-use either::Either::Left;
-use key_derivation::{ConcordiumHdWallet, CredentialContext, Net};
-use rand::thread_rng;
+use concordium_rust_bindings_common::{
+    helpers::{deserialize_type_value, serialize_type_value},
+    types::{HexString, JsonString},
+};
+use either::Either::{self, Left, Right};
+use key_derivation::{ConcordiumHdWallet, Net};
+use num_bigint::BigUint;
+use rand::{thread_rng, RngCore};
 use serde::{Deserialize as SerdeDeserialize, Serialize as SerdeSerialize};
 use serde_json::{from_str, from_value, Value as SerdeValue};
+use sha2::{Digest, Sha256};
 use std::{collections::BTreeMap, convert::TryInto};
+use zeroize::{Zeroize, Zeroizing};
 
 #[derive(SerdeSerialize, SerdeDeserialize)]
 #[serde(bound(serialize = "P: Pairing", deserialize = "P: Pairing"))]
@@ -103,14 +150,542 @@ pub struct CredId {
     pub cred_id: constants::ArCurve,
 }
 
-fn get_net(net: &str) -> Result<Net> {
-    Ok(match net {
-        "Mainnet" => Net::Mainnet,
-        "Testnet" => Net::Testnet,
-        _ => bail!("Unknown net"),
+/// The seed and net of a wallet, given as part of the JSON input of the
+/// functions that use the wallet.
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedInput {
+    seed_as_hex: HexString,
+    net: String,
+}
+
+impl SeedInput {
+    pub fn wallet(&self) -> Result<Wallet> {
+        wallet_from_hex(&self.seed_as_hex, &self.net)
+    }
+}
+
+/// Call `f` with the wallet and identity of the input of a `wallet_library`
+/// function, which takes the secrets of the identity instead of a seed. The
+/// wallet is either the given one, or the one of the `seedAsHex` and `net` of
+/// the input. The input is returned unchanged if there is neither. The
+/// identity is given by the `identityIndex` of the input and the identity
+/// provider of `ipInfo`.
+fn with_input_identity(
+    wallet: Option<&Wallet>,
+    input: SerdeValue,
+    f: impl FnOnce(&Wallet, u32, u32, SerdeValue) -> Result<SerdeValue>,
+) -> Result<SerdeValue> {
+    let input_wallet;
+    let wallet = match wallet {
+        Some(wallet) => wallet,
+        None if input.get("seedAsHex").is_some() => {
+            let seed: SeedInput = from_value(input.clone())?;
+            input_wallet = seed.wallet()?;
+            &input_wallet
+        }
+        None => return Ok(input),
+    };
+    let ip_info: IpInfo<constants::IpPairing> = try_get(&input, "ipInfo")?;
+    let identity_index: u32 = try_get(&input, "identityIndex")?;
+    f(wallet, ip_info.ip_identity.0, identity_index, input)
+}
+
+/// Insert the given fields into the input of a `wallet_library` function,
+/// removing the seed from the input.
+fn insert_secrets(mut input: SerdeValue, secrets: SerdeValue) -> Result<SerdeValue> {
+    let (Some(fields), SerdeValue::Object(secrets)) = (input.as_object_mut(), secrets) else {
+        bail!("The input must be an object");
+    };
+    fields.remove("seedAsHex");
+    fields.remove("net");
+    fields.extend(secrets);
+    Ok(input)
+}
+
+/// Derive the `idCredSec`, `prfKey` and `blindingRandomness` of the input of
+/// `create_identity_object_request_v1_aux` from the given wallet or the seed
+/// of the input, if there is one. This allows creating identity requests on
+/// all networks supported by [`Wallet`].
+pub fn with_id_request_secrets(wallet: Option<&Wallet>, input: SerdeValue) -> Result<SerdeValue> {
+    with_input_identity(wallet, input, |wallet, ip_index, identity_index, input| {
+        let secrets = json!({
+            "idCredSec": base16_encode_string(&wallet.get_id_cred_sec(ip_index, identity_index)?),
+            "prfKey": base16_encode_string(&wallet.get_prf_key(ip_index, identity_index)?),
+            "blindingRandomness": base16_encode_string(
+                &wallet.get_blinding_randomness(ip_index, identity_index)?
+            ),
+        });
+        insert_secrets(input, secrets)
+    })
+}
+
+/// Derive the `idCredSec` of the input of
+/// `create_identity_recovery_request_aux` from the given wallet or the seed of
+/// the input, if there is one.
+pub fn with_identity_recovery_secrets(
+    wallet: Option<&Wallet>,
+    input: SerdeValue,
+) -> Result<SerdeValue> {
+    with_input_identity(wallet, input, |wallet, ip_index, identity_index, input| {
+        let secrets = json!({
+            "idCredSec": base16_encode_string(&wallet.get_id_cred_sec(ip_index, identity_index)?),
+        });
+        insert_secrets(input, secrets)
     })
 }
 
+/// Derive the secrets of the identity, the randomness of the commitments to
+/// the attributes of the identity object and, unless given, the public key of
+/// the credential of the input of `create_unsigned_credential_v1_aux` from the
+/// given wallet or the seed of the input, if there is one.
+pub fn with_unsigned_credential_secrets(
+    wallet: Option<&Wallet>,
+    input: SerdeValue,
+) -> Result<SerdeValue> {
+    with_input_identity(wallet, input, |wallet, ip_index, identity_index, input| {
+        let cred_number: u8 = try_get(&input, "credNumber")?;
+        let id_object: IdentityObjectV1<constants::IpPairing, ArCurve, AttributeKind> =
+            try_get(&input, "idObject")?;
+        let attribute_randomness = id_object
+            .alist
+            .alist
+            .keys()
+            .map(|&tag| {
+                let randomness = wallet.get_attribute_commitment_randomness(
+                    ip_index,
+                    identity_index,
+                    u32::from(cred_number),
+                    tag,
+                )?;
+                Ok((tag, base16_encode_string(&randomness)))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let mut secrets = json!({
+            "idCredSec": base16_encode_string(&wallet.get_id_cred_sec(ip_index, identity_index)?),
+            "prfKey": base16_encode_string(&wallet.get_prf_key(ip_index, identity_index)?),
+            "sigRetrievelRandomness": base16_encode_string(
+                &wallet.get_blinding_randomness(ip_index, identity_index)?
+            ),
+            "attributeRandomness": attribute_randomness,
+        });
+        if input.get("credentialPublicKeys").is_none() {
+            let public_key =
+                wallet.get_account_public_key(ip_index, identity_index, u32::from(cred_number))?;
+            secrets["credentialPublicKeys"] = json!(CredentialPublicKeys {
+                keys: BTreeMap::from([(KeyIndex(0), VerifyKey::Ed25519VerifyKey(public_key))]),
+                threshold: SignatureThreshold::ONE,
+            });
+        }
+        insert_secrets(input, secrets)
+    })
+}
+
+/// A wallet deriving the keys of identities, accounts and verifiable
+/// credentials from a seed for a network. The network is given as `Mainnet`,
+/// `Testnet` or a named network with an explicit coin type, written as
+/// `<name>:<coin type>`, e.g. `Stagenet:7919`.
+///
+/// Keys are derived with SLIP-10 at hardened paths below `m/44'/<coin type>'`,
+/// where the coin type of Mainnet and Testnet is the one of [`Net`]. On other
+/// networks the keys are derived at the same paths as on Mainnet and Testnet
+/// with the coin type replaced. Keys with a key index other than 0 of a
+/// credential are derived at the hardened child with the key index of the path
+/// of the account signing key.
+///
+/// On all networks, the validator keys of the account of a credential are
+/// derived below `m/44'/<coin type>'/<ip>'/<identity>'/6'/<cred>'/<key index>'`,
+/// where the key index allows rotating the keys of the validator. The election
+/// and signature keys are the keys derived at the children `0'` and `1'`, and
+/// the aggregation key is the BLS key generated from the key derived at the
+/// child `2'`.
+pub enum Wallet {
+    /// A wallet on Mainnet or Testnet, using the key derivation library.
+    Known(ConcordiumHdWallet),
+    /// A wallet on a network with a custom coin type.
+    Custom { seed: [u8; 64], coin_type: u32 },
+}
+
+impl Drop for Wallet {
+    fn drop(&mut self) {
+        match self {
+            Wallet::Known(wallet) => wallet.seed.zeroize(),
+            Wallet::Custom { seed, .. } => seed.zeroize(),
+        }
+    }
+}
+
+/// Parse a network, which is either one of the networks known by the key
+/// derivation library or a named network with a custom coin type. The name
+/// of a custom network is only used in error messages, the keys only depend
+/// on the coin type.
+fn get_net(raw_net: &str) -> Result<Either<Net, u32>> {
+    match raw_net {
+        "Mainnet" => Ok(Left(Net::Mainnet)),
+        "Testnet" => Ok(Left(Net::Testnet)),
+        _ => {
+            let Some((name, coin_type)) = raw_net.split_once(':') else {
+                bail!(
+                    "Unknown net {}, expected Mainnet, Testnet or <name>:<coin type>",
+                    raw_net
+                );
+            };
+            ensure!(!name.is_empty(), "The name of the net must not be empty");
+            let coin_type: u32 = coin_type
+                .parse()
+                .with_context(|| format!("Invalid coin type of net {}", name))?;
+            ensure!(
+                ed25519_hd_key_derivation::checked_harden(coin_type).is_ok(),
+                "The coin type {} of net {} must be less than 2^31",
+                coin_type,
+                name
+            );
+            ensure!(
+                coin_type != Net::Mainnet.net_code() && coin_type != Net::Testnet.net_code(),
+                "The coin type {} of net {} is used by Mainnet or Testnet",
+                coin_type,
+                name
+            );
+            Ok(Right(coin_type))
+        }
+    }
+}
+
+impl Wallet {
+    /// Create a wallet on the given net. The wallet keeps a copy of the seed,
+    /// which is zeroized when the wallet is dropped, while the given seed is
+    /// left to the caller to zeroize.
+    pub fn new(seed: &[u8; 64], raw_net: &str) -> Result<Self> {
+        Ok(match get_net(raw_net)? {
+            Left(net) => Wallet::Known(ConcordiumHdWallet { seed: *seed, net }),
+            Right(coin_type) => Wallet::Custom {
+                seed: *seed,
+                coin_type,
+            },
+        })
+    }
+
+    fn seed(&self) -> &[u8; 64] {
+        match self {
+            Wallet::Known(wallet) => &wallet.seed,
+            Wallet::Custom { seed, .. } => seed,
+        }
+    }
+
+    /// The coin type used in the derivation paths of the wallet.
+    pub fn coin_type(&self) -> u32 {
+        match self {
+            Wallet::Known(wallet) => wallet.net.net_code(),
+            Wallet::Custom { coin_type, .. } => *coin_type,
+        }
+    }
+
+    /// Derive the private key at the hardened path `m/44'/<coin type>'/<path>`.
+    fn derive_private_key(&self, path: &[u32]) -> Result<Zeroizing<[u8; 32]>> {
+        let path = [44, self.coin_type()]
+            .iter()
+            .chain(path)
+            .map(|&index| ed25519_hd_key_derivation::checked_harden(index))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("Invalid derivation path: {:?}", e))?;
+        let mut keys = ed25519_hd_key_derivation::derive_from_parsed_path(&path, self.seed())
+            .map_err(|e| anyhow!("Unable to derive key: {:?}", e))?;
+        let private_key = Zeroizing::new(keys.private_key);
+        keys.private_key.zeroize();
+        keys.chain_code.zeroize();
+        Ok(private_key)
+    }
+
+    /// Derive the BLS key generated from the private key at the given path.
+    fn derive_scalar(&self, path: &[u32]) -> Result<<ArCurve as Curve>::Scalar> {
+        let key = self.derive_private_key(path)?;
+        keygen_bls::keygen_bls(key.as_ref(), b"")
+            .map_err(|e| anyhow!("Unable to generate key: {:?}", e))
+    }
+
+    pub fn get_account_signing_key(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u32,
+    ) -> Result<SigningKey> {
+        Ok(match self {
+            Wallet::Known(wallet) => wallet
+                .get_account_signing_key(
+                    identity_provider_index,
+                    identity_index,
+                    credential_counter,
+                )?
+                .into(),
+            Wallet::Custom { .. } => SigningKey::from_bytes(&*self.derive_private_key(&[
+                identity_provider_index,
+                identity_index,
+                0,
+                credential_counter,
+            ])?),
+        })
+    }
+
+    pub fn get_account_public_key(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u32,
+    ) -> Result<PublicKey> {
+        Ok(match self {
+            Wallet::Known(wallet) => wallet.get_account_public_key(
+                identity_provider_index,
+                identity_index,
+                credential_counter,
+            )?,
+            Wallet::Custom { .. } => self
+                .get_account_signing_key(
+                    identity_provider_index,
+                    identity_index,
+                    credential_counter,
+                )?
+                .verifying_key(),
+        })
+    }
+
+    /// Derive the signing key with the given key index for a credential. The
+    /// key with index 0 is the account signing key of the credential, and any
+    /// other key is derived at the hardened child with the key index of the
+    /// path of the account signing key.
+    pub fn get_credential_signing_key(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u32,
+        key_index: u8,
+    ) -> Result<SigningKey> {
+        if key_index == 0 {
+            return self.get_account_signing_key(
+                identity_provider_index,
+                identity_index,
+                credential_counter,
+            );
+        }
+        Ok(SigningKey::from_bytes(&*self.derive_private_key(&[
+            identity_provider_index,
+            identity_index,
+            0,
+            credential_counter,
+            u32::from(key_index),
+        ])?))
+    }
+
+    pub fn get_id_cred_sec(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+    ) -> Result<<ArCurve as Curve>::Scalar> {
+        match self {
+            Wallet::Known(wallet) => {
+                Ok(wallet.get_id_cred_sec(identity_provider_index, identity_index)?)
+            }
+            Wallet::Custom { .. } => {
+                self.derive_scalar(&[identity_provider_index, identity_index, 2])
+            }
+        }
+    }
+
+    pub fn get_prf_key(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+    ) -> Result<prf::SecretKey<ArCurve>> {
+        match self {
+            Wallet::Known(wallet) => {
+                Ok(wallet.get_prf_key(identity_provider_index, identity_index)?)
+            }
+            Wallet::Custom { .. } => Ok(prf::SecretKey::new(self.derive_scalar(&[
+                identity_provider_index,
+                identity_index,
+                3,
+            ])?)),
+        }
+    }
+
+    pub fn get_blinding_randomness(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+    ) -> Result<SigRetrievalRandomness<constants::IpPairing>> {
+        match self {
+            Wallet::Known(wallet) => {
+                Ok(wallet.get_blinding_randomness(identity_provider_index, identity_index)?)
+            }
+            Wallet::Custom { .. } => Ok(SigRetrievalRandomness::new(self.derive_scalar(&[
+                identity_provider_index,
+                identity_index,
+                4,
+            ])?)),
+        }
+    }
+
+    pub fn get_attribute_commitment_randomness(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u32,
+        attribute: AttributeTag,
+    ) -> Result<PedersenRandomness<ArCurve>> {
+        match self {
+            Wallet::Known(wallet) => Ok(wallet.get_attribute_commitment_randomness(
+                identity_provider_index,
+                identity_index,
+                credential_counter,
+                attribute,
+            )?),
+            Wallet::Custom { .. } => Ok(PedersenRandomness::new(self.derive_scalar(&[
+                identity_provider_index,
+                identity_index,
+                5,
+                credential_counter,
+                u32::from(attribute.0),
+            ])?)),
+        }
+    }
+
+    pub fn get_verifiable_credential_signing_key(
+        &self,
+        issuer: ContractAddress,
+        verifiable_credential_index: u32,
+    ) -> Result<SigningKey> {
+        Ok(match self {
+            Wallet::Known(wallet) => wallet
+                .get_verifiable_credential_signing_key(issuer, verifiable_credential_index)?
+                .into(),
+            Wallet::Custom { .. } => SigningKey::from_bytes(
+                &*self.derive_private_key(&[
+                    1,
+                    issuer
+                        .index
+                        .try_into()
+                        .context("The issuer index is too large")?,
+                    issuer
+                        .subindex
+                        .try_into()
+                        .context("The issuer subindex is too large")?,
+                    verifiable_credential_index,
+                ])?,
+            ),
+        })
+    }
+
+    pub fn get_verifiable_credential_public_key(
+        &self,
+        issuer: ContractAddress,
+        verifiable_credential_index: u32,
+    ) -> Result<PublicKey> {
+        Ok(match self {
+            Wallet::Known(wallet) => {
+                wallet.get_verifiable_credential_public_key(issuer, verifiable_credential_index)?
+            }
+            Wallet::Custom { .. } => self
+                .get_verifiable_credential_signing_key(issuer, verifiable_credential_index)?
+                .verifying_key(),
+        })
+    }
+
+    pub fn get_verifiable_credential_backup_encryption_key(&self) -> Result<Zeroizing<[u8; 32]>> {
+        match self {
+            Wallet::Known(wallet) => Ok(Zeroizing::new(
+                *wallet
+                    .get_verifiable_credential_backup_encryption_key()?
+                    .as_bytes(),
+            )),
+            Wallet::Custom { .. } => self.derive_private_key(&[2]),
+        }
+    }
+
+    /// Derive the validator keys of the account of the credential. Each of
+    /// the secret keys is derived at its own path, such that the keys can be
+    /// recovered from the seed.
+    pub fn get_baker_keys(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u32,
+        key_index: u32,
+    ) -> Result<BakerKeyPairs> {
+        let path = |key: u32| {
+            [
+                identity_provider_index,
+                identity_index,
+                6,
+                credential_counter,
+                key_index,
+                key,
+            ]
+        };
+        let election_sign: BakerElectionSignKey =
+            from_bytes(&mut self.derive_private_key(&path(0))?.as_slice())?;
+        let signature_sign: BakerSignatureSignKey =
+            from_bytes(&mut self.derive_private_key(&path(1))?.as_slice())?;
+        let aggregation_sign: BakerAggregationSignKey =
+            from_bytes(&mut to_bytes(&self.derive_scalar(&path(2))?).as_slice())?;
+        Ok(BakerKeyPairs {
+            election_verify: BakerElectionVerifyKey::from(&election_sign),
+            election_sign,
+            signature_verify: BakerSignatureVerifyKey::from(&signature_sign),
+            signature_sign,
+            aggregation_verify: BakerAggregationVerifyKey::from(&aggregation_sign),
+            aggregation_sign,
+        })
+    }
+}
+
+/// The credential of a wallet, used to derive the randomness of the
+/// commitments to the attributes of the credential.
+struct WalletCredentialContext<'a> {
+    wallet: &'a Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_index: u8,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unable to derive attribute commitment randomness: {0}")]
+struct AttributeRandomnessError(String);
+
+impl HasAttributeRandomness<ArCurve> for WalletCredentialContext<'_> {
+    type ErrorType = AttributeRandomnessError;
+
+    fn get_attribute_commitment_randomness(
+        &self,
+        attribute_tag: &AttributeTag,
+    ) -> Result<PedersenRandomness<ArCurve>, Self::ErrorType> {
+        self.wallet
+            .get_attribute_commitment_randomness(
+                self.identity_provider_index,
+                self.identity_index,
+                u32::from(self.credential_index),
+                *attribute_tag,
+            )
+            .map_err(|e| AttributeRandomnessError(e.to_string()))
+    }
+}
+
+/// Create a wallet from a hex-encoded 64 byte seed.
+pub fn wallet_from_hex(seed_as_hex: &str, raw_net: &str) -> Result<Wallet> {
+    let seed_decoded = Zeroizing::new(hex::decode(seed_as_hex)?);
+    let seed: &[u8; 64] = match seed_decoded.as_slice().try_into() {
+        Ok(seed) => seed,
+        Err(_) => bail!("The provided seed {} was not 64 bytes", seed_as_hex),
+    };
+    Wallet::new(seed, raw_net)
+}
+
+/// Create a wallet from the raw bytes of a 64 byte seed.
+pub fn wallet_from_seed(seed: &[u8], raw_net: &str) -> Result<Wallet> {
+    let seed: &[u8; 64] = seed
+        .try_into()
+        .map_err(|_| anyhow!("The provided seed was not 64 bytes"))?;
+    Wallet::new(seed, raw_net)
+}
+
 #[derive(SerdeSerialize, SerdeDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CredentialInput {
@@ -119,78 +694,144 @@ pub struct CredentialInput {
     ars_infos: BTreeMap<ArIdentity, ArInfo<constants::ArCurve>>,
     id_object: IdentityObjectV1<constants::IpPairing, constants::ArCurve, AttributeKind>,
     revealed_attributes: Vec<AttributeTag>,
-    seed_as_hex: HexString,
-    net: String,
     identity_index: u32,
     cred_number: u8,
-    expiry: TransactionTime,
+    /// The expiry of the transaction creating a new account. Exactly one of
+    /// `expiry` and `address` must be present.
+    expiry: Option<TransactionTime>,
+    /// The address of an existing account the credential is added to.
+    address: Option<AccountAddress>,
+    /// The keys of the credential, where the position in the list is the key
+    /// index. At most one of `keys` and `keyCount` can be present, and if
+    /// neither is, a single key derived from the seed is used.
+    keys: Option<Vec<CredentialKeyInput>>,
+    /// The number of keys to derive from the seed.
+    key_count: Option<u8>,
+    /// The signature threshold of the credential. Defaults to 1.
+    threshold: Option<SignatureThreshold>,
+    /// Whether to return the keys of the credential, including the secret
+    /// signing keys, alongside the credential. Defaults to false.
+    return_keys: Option<bool>,
 }
 
-pub fn create_credential_v1_aux(input: CredentialInput) -> Result<JsonString> {
-    let seed_decoded = hex::decode(&input.seed_as_hex)?;
-    let seed: [u8; 64] = match seed_decoded.try_into() {
-        Ok(s) => s,
-        Err(_) => bail!("The provided seed {} was not 64 bytes", input.seed_as_hex),
-    };
+/// The source of a key of a credential.
+#[derive(SerdeSerialize, SerdeDeserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CredentialKeyInput {
+    /// Derive the key from the seed.
+    Seed,
+    /// Use the given key pair.
+    External(Box<KeyPair>),
+}
 
-    let wallet = ConcordiumHdWallet {
-        seed,
-        net: get_net(&input.net)?,
-    };
+impl CredentialInput {
+    /// Build the keys and threshold of the credential.
+    fn credential_data(
+        &self,
+        wallet: &Wallet,
+        identity_provider_index: u32,
+    ) -> Result<CredentialData> {
+        // The external key pairs of the credential, where `None` means that
+        // the key is derived from the seed.
+        let sources: Vec<Option<&KeyPair>> = match (&self.keys, self.key_count) {
+            (Some(keys), None) => keys
+                .iter()
+                .map(|key| match key {
+                    CredentialKeyInput::Seed => None,
+                    CredentialKeyInput::External(keypair) => Some(keypair.as_ref()),
+                })
+                .collect(),
+            (None, count) => vec![None; usize::from(count.unwrap_or(1))],
+            (Some(_), Some(_)) => bail!("Only one of keys and keyCount can be provided"),
+        };
+        if sources.is_empty() {
+            bail!("A credential must have at least one key");
+        }
 
-    let identity_provider_index = input.ip_info.ip_identity.0;
+        let mut keys = BTreeMap::new();
+        for (index, source) in sources.into_iter().enumerate() {
+            let key_index: u8 = index
+                .try_into()
+                .map_err(|_| anyhow!("A credential can have at most 255 keys"))?;
+            let keypair = match source {
+                None => KeyPair::from(wallet.get_credential_signing_key(
+                    identity_provider_index,
+                    self.identity_index,
+                    u32::from(self.cred_number),
+                    key_index,
+                )?),
+                Some(keypair) => keypair.clone(),
+            };
+            keys.insert(KeyIndex(key_index), keypair);
+        }
+
+        let threshold = self.threshold.unwrap_or(SignatureThreshold::ONE);
+        if usize::from(u8::from(threshold)) > keys.len() {
+            bail!(
+                "The threshold {} exceeds the number of keys {}",
+                u8::from(threshold),
+                keys.len()
+            );
+        }
+        Ok(CredentialData { keys, threshold })
+    }
+}
 
+/// Derive the secret data for using the identity with the given index from
+/// the seed.
+fn id_object_use_data(
+    wallet: &Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+) -> Result<IdObjectUseData<constants::IpPairing, ArCurve>> {
     let prf_key: prf::SecretKey<ArCurve> =
-        wallet.get_prf_key(identity_provider_index, input.identity_index)?;
+        wallet.get_prf_key(identity_provider_index, identity_index)?;
 
     let id_cred_sec: PedersenValue<ArCurve> =
-        PedersenValue::new(wallet.get_id_cred_sec(identity_provider_index, input.identity_index)?);
+        PedersenValue::new(wallet.get_id_cred_sec(identity_provider_index, identity_index)?);
     let id_cred: IdCredentials<ArCurve> = IdCredentials { id_cred_sec };
 
     let sig_retrievel_randomness: concordium_base::id::ps_sig::SigRetrievalRandomness<
         constants::IpPairing,
-    > = wallet.get_blinding_randomness(identity_provider_index, input.identity_index)?;
+    > = wallet.get_blinding_randomness(identity_provider_index, identity_index)?;
 
     let chi = CredentialHolderInfo::<ArCurve> { id_cred };
     let aci = AccCredentialInfo {
         cred_holder_info: chi,
         prf_key,
     };
-    let id_use_data = IdObjectUseData {
+    Ok(IdObjectUseData {
         aci,
         randomness: sig_retrievel_randomness,
-    };
+    })
+}
 
-    // For now we can only create new accounts and do not support
-    // adding credentials onto existing ones. Once that is supported the address
-    // should be coming from the input data.
-    let new_or_existing = Left(input.expiry);
+fn create_credential_v1(
+    wallet: &Wallet,
+    input: CredentialInput,
+) -> Result<(
+    CredentialDeploymentInfo<constants::IpPairing, constants::ArCurve, AttributeKind>,
+    CredentialData,
+)> {
+    let identity_provider_index = input.ip_info.ip_identity.0;
 
-    let cred_data = {
-        let mut keys = std::collections::BTreeMap::new();
-        let signing_key: SigningKey = wallet
-            .get_account_signing_key(
-                identity_provider_index,
-                input.identity_index,
-                u32::from(input.cred_number),
-            )?
-            .into();
-        let keypair = KeyPair::from(signing_key);
-        keys.insert(KeyIndex(0), keypair);
-
-        CredentialData {
-            keys,
-            threshold: SignatureThreshold::ONE,
-        }
+    let id_use_data = id_object_use_data(wallet, identity_provider_index, input.identity_index)?;
+
+    let new_or_existing = match (input.expiry, input.address) {
+        (Some(expiry), None) => Left(expiry),
+        (None, Some(address)) => Right(address),
+        _ => bail!("Exactly one of expiry and address must be provided"),
     };
 
+    let cred_data = input.credential_data(wallet, identity_provider_index)?;
+
     let context = IpContext::new(&input.ip_info, &input.ars_infos, &input.global_context);
 
     let policy = build_policy(&input.id_object.alist, input.revealed_attributes)?;
 
-    let credential_context = CredentialContext {
+    let credential_context = WalletCredentialContext {
         wallet,
-        identity_provider_index: identity_provider_index.into(),
+        identity_provider_index,
         identity_index: input.identity_index,
         credential_index: input.cred_number,
     };
@@ -206,8 +847,75 @@ pub fn create_credential_v1_aux(input: CredentialInput) -> Result<JsonString> {
         &new_or_existing,
     )?;
 
-    let cdi_json = json!(cdi);
-    Ok(cdi_json.to_string())
+    Ok((cdi, cred_data))
+}
+
+/// Create a credential from the seed, either for a new account or for adding
+/// onto an existing account. If `returnKeys` is set, the credential is
+/// returned together with the keys of the credential, otherwise only the
+/// credential is returned.
+pub fn create_credential_v1_aux(wallet: &Wallet, input: CredentialInput) -> Result<JsonString> {
+    let return_keys = input.return_keys.unwrap_or(false);
+    let (cdi, keys) = create_credential_v1(wallet, input)?;
+    let response = if return_keys {
+        json!({
+            "credential": cdi,
+            "keys": keys,
+        })
+    } else {
+        json!(cdi)
+    };
+    Ok(response.to_string())
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExistingAccountCredentialInput {
+    /// The credential to create, which must have the `address` of the
+    /// existing account.
+    #[serde(flatten)]
+    credential: CredentialInput,
+    /// The index of the new credential on the account.
+    credential_index: u8,
+    /// The signature threshold of the account after adding the credential.
+    new_threshold: AccountThreshold,
+}
+
+/// Create a credential from the seed for an existing account, together with
+/// the unsigned payload of the transaction adding it to the account. The keys
+/// of the credential are only included if `returnKeys` is set.
+pub fn create_existing_account_credential_v1_aux(
+    wallet: &Wallet,
+    input: ExistingAccountCredentialInput,
+) -> Result<JsonString> {
+    if input.credential.address.is_none() {
+        bail!("The address of the existing account must be provided");
+    }
+    let return_keys = input.credential.return_keys.unwrap_or(false);
+
+    let (cdi, keys) = create_credential_v1(wallet, input.credential)?;
+    let mut new_cred_infos = BTreeMap::new();
+    new_cred_infos.insert(
+        CredentialIndex {
+            index: input.credential_index,
+        },
+        cdi.clone(),
+    );
+    let payload = Payload::UpdateCredentials {
+        new_cred_infos,
+        remove_cred_ids: Vec::new(),
+        new_threshold: input.new_threshold,
+    };
+
+    let mut response = json!({
+        "credential": cdi,
+        "payload": payload,
+        "serializedPayload": hex::encode(to_bytes(&payload)),
+    });
+    if return_keys {
+        response["keys"] = json!(keys);
+    }
+    Ok(response.to_string())
 }
 
 pub fn generate_unsigned_credential_aux(input: &str) -> Result<JsonString> {
@@ -372,8 +1080,6 @@ pub fn get_credential_deployment_info_aux(
 pub struct IdProofInput {
     id_object: IdentityObjectV1<constants::IpPairing, constants::ArCurve, AttributeKind>,
     global_context: GlobalContext<constants::ArCurve>,
-    seed_as_hex: String,
-    net: String,
     identity_provider_index: u32,
     identity_index: u32,
     cred_number: u8,
@@ -387,31 +1093,20 @@ struct IdProofOutput {
     proof: Versioned<Proof<constants::ArCurve, AttributeKind>>,
 }
 
-pub fn create_id_proof_aux(input: IdProofInput) -> Result<JsonString> {
-    let seed_decoded = hex::decode(&input.seed_as_hex)?;
-    let seed: [u8; 64] = match seed_decoded.try_into() {
-        Ok(s) => s,
-        Err(_) => bail!("The provided seed {} was not 64 bytes", input.seed_as_hex),
-    };
-
+pub fn create_id_proof_aux(wallet: &Wallet, input: IdProofInput) -> Result<JsonString> {
     let challenge_decoded = hex::decode(&input.challenge)?;
 
-    let wallet = ConcordiumHdWallet {
-        seed,
-        net: get_net(&input.net)?,
-    };
-
     let attribute_list = input.id_object.alist;
 
     let cred_id_exponent = wallet
         .get_prf_key(input.identity_provider_index, input.identity_index)?
         .prf_exponent(input.cred_number)?;
 
-    let credential_context = CredentialContext {
+    let credential_context = WalletCredentialContext {
         wallet,
-        identity_provider_index: input.identity_provider_index.into(),
-        credential_index: input.cred_number,
+        identity_provider_index: input.identity_provider_index,
         identity_index: input.identity_index,
+        credential_index: input.cred_number,
     };
 
     let credential = input
@@ -444,6 +1139,42 @@ pub fn create_id_proof_aux(input: IdProofInput) -> Result<JsonString> {
     Ok(json!(out).to_string())
 }
 
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyIdProofInput {
+    statement: Statement<constants::ArCurve, AttributeKind>,
+    proof: Versioned<Proof<constants::ArCurve, AttributeKind>>,
+    challenge: HexString,
+    global_context: GlobalContext<constants::ArCurve>,
+    /// The id of the credential, i.e. the `credential` of the proof output.
+    #[serde(deserialize_with = "base16_decode")]
+    credential: constants::ArCurve,
+    /// The commitments of the credential, as found on chain.
+    commitments: CredentialDeploymentCommitments<constants::ArCurve>,
+}
+
+/// Verify a proof created by `create_id_proof_aux` of a statement about the
+/// attributes of a credential, against the on chain commitments of the
+/// credential.
+pub fn verify_id_proof_aux(input: VerifyIdProofInput) -> Result<bool> {
+    let challenge = hex::decode(&input.challenge)?;
+    ensure!(
+        input.proof.version == VERSION_0,
+        "Unsupported proof version"
+    );
+    let statement = StatementWithContext {
+        credential: input.credential,
+        statement: input.statement,
+    };
+    Ok(statement.verify(
+        ProofVersion::Version1,
+        &challenge,
+        &input.global_context,
+        &input.commitments,
+        &input.proof.value,
+    ))
+}
+
 #[derive(SerdeDeserialize)]
 struct Web3SecretKey(
     #[serde(deserialize_with = "base16_decode")] concordium_base::ed25519::SecretKey,
@@ -520,9 +1251,14 @@ pub struct BakerKeys {
 }
 
 pub fn generate_baker_keys(sender: AccountAddress) -> Result<JsonString> {
-    let mut csprng = thread_rng();
-    let keys = BakerKeyPairs::generate(&mut csprng);
-    let keys_payload = ConfigureBakerKeysPayload::new(&keys, sender, &mut csprng);
+    let keys = BakerKeyPairs::generate(&mut thread_rng());
+    baker_keys_output(keys, sender)
+}
+
+/// Output the keys together with the payload for registering them for the
+/// sender, in the format of `generate_baker_keys`.
+fn baker_keys_output(keys: BakerKeyPairs, sender: AccountAddress) -> Result<JsonString> {
+    let keys_payload = ConfigureBakerKeysPayload::new(&keys, sender, &mut thread_rng());
     let output = BakerKeys {
         keys_payload,
         election_private_key: keys.election_sign,
@@ -532,6 +1268,145 @@ pub fn generate_baker_keys(sender: AccountAddress) -> Result<JsonString> {
     Ok(serde_json::to_string(&output)?)
 }
 
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeriveBakerKeysInput {
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u32,
+    /// The index of the keys of the validator, which is increased to rotate the
+    /// keys. Defaults to 0.
+    #[serde(default)]
+    key_index: u32,
+    sender: AccountAddress,
+}
+
+/// Derive the validator keys of the account of a credential from the seed,
+/// such that they can be recovered from the seed, in the format of
+/// `generate_baker_keys`.
+pub fn derive_baker_keys_aux(wallet: &Wallet, input: DeriveBakerKeysInput) -> Result<JsonString> {
+    let keys = wallet.get_baker_keys(
+        input.identity_provider_index,
+        input.identity_index,
+        input.credential_counter,
+        input.key_index,
+    )?;
+    baker_keys_output(keys, input.sender)
+}
+
+/// The credentials file read by the node, `baker-credentials.json`.
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct BakerCredentials {
+    baker_id: BakerId,
+    #[serde(flatten)]
+    keys: BakerKeyPairs,
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BakerCredentialsInput {
+    /// The id of the validator, which is the index of its account.
+    baker_id: BakerId,
+    /// The private and public keys of the validator, e.g. the output of
+    /// `generate_baker_keys`.
+    keys: BakerKeyPairs,
+}
+
+/// Create the `baker-credentials.json` file the node reads the keys of the
+/// validator from.
+pub fn create_baker_credentials_file_aux(input: BakerCredentialsInput) -> Result<JsonString> {
+    let credentials = BakerCredentials {
+        baker_id: input.baker_id,
+        keys: input.keys,
+    };
+    Ok(serde_json::to_string_pretty(&credentials)?)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigureBakerKeysPayloadInput {
+    /// The keys of the validator, e.g. the output of `generate_baker_keys` or
+    /// the contents of `baker-credentials.json`.
+    keys: BakerKeyPairs,
+    sender: AccountAddress,
+}
+
+/// Create the payload for registering existing validator keys for the sender,
+/// including the proofs of ownership of the keys.
+pub fn create_configure_baker_keys_payload_aux(
+    input: ConfigureBakerKeysPayloadInput,
+) -> Result<JsonString> {
+    let payload = ConfigureBakerKeysPayload::new(&input.keys, input.sender, &mut thread_rng());
+    Ok(serde_json::to_string(&payload)?)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyConfigureBakerKeysInput {
+    payload: ConfigureBakerKeysPayload,
+    sender: AccountAddress,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigureBakerKeysReport {
+    is_valid: bool,
+    election_proof_valid: bool,
+    signature_proof_valid: bool,
+    aggregation_proof_valid: bool,
+}
+
+/// Read a validator verification key as the key type it wraps. The wrappers
+/// serialize exactly as the underlying key, which they do not expose.
+fn unwrap_baker_key<A: Deserial>(key: &impl Serial) -> Result<A> {
+    from_bytes(&mut to_bytes(key).as_slice())
+}
+
+/// Verify the proofs of ownership of the validator keys in a payload, as
+/// created by `ConfigureBakerKeysPayload::new` for the sender. The proofs are
+/// bound to the sender and all three keys, so a payload made for another
+/// account fails all of them.
+// The challenge must be the one the chain checks `ConfigureBaker` proofs
+// against, which is still built with the deprecated `RandomOracle::domain`.
+#[allow(deprecated)]
+pub fn verify_configure_baker_keys_aux(input: VerifyConfigureBakerKeysInput) -> Result<JsonString> {
+    let payload = &input.payload;
+    let mut challenge = b"configureBaker".to_vec();
+    input.sender.serial(&mut challenge);
+    payload.election_verify_key.serial(&mut challenge);
+    payload.signature_verify_key.serial(&mut challenge);
+    payload.aggregation_verify_key.serial(&mut challenge);
+
+    let election_key: PublicKey = unwrap_baker_key(&payload.election_verify_key)?;
+    let signature_key: PublicKey = unwrap_baker_key(&payload.signature_verify_key)?;
+    let aggregation_key: aggregate_sig::PublicKey<AggregateSigPairing> =
+        unwrap_baker_key(&payload.aggregation_verify_key)?;
+
+    let election_proof_valid = verify_dlog_ed25519(
+        &mut RandomOracle::domain(&challenge),
+        &election_key,
+        &payload.proof_election,
+    );
+    let signature_proof_valid = verify_dlog_ed25519(
+        &mut RandomOracle::domain(&challenge),
+        &signature_key,
+        &payload.proof_sig,
+    );
+    let aggregation_proof_valid = aggregation_key.check_proof(
+        &mut RandomOracle::domain(&challenge),
+        &payload.proof_aggregation,
+    );
+
+    let report = ConfigureBakerKeysReport {
+        is_valid: election_proof_valid && signature_proof_valid && aggregation_proof_valid,
+        election_proof_valid,
+        signature_proof_valid,
+        aggregation_proof_valid,
+    };
+    Ok(serde_json::to_string(&report)?)
+}
+
 #[derive(SerdeDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VerifyWeb3IdCredentialSignatureInput {
@@ -572,3 +1447,4457 @@ pub fn verify_web3_id_credential_signature_aux(
         input.issuer_contract,
     ))
 }
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignWeb3IdCredentialInput {
+    global_context: GlobalContext<constants::ArCurve>,
+    values: BTreeMap<String, Web3IdAttribute>,
+    holder: CredentialHolderId,
+    issuer_contract: ContractAddress,
+    issuer_key: Web3SecretKey,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedWeb3IdCredential {
+    #[serde(serialize_with = "base16_encode")]
+    signature: concordium_base::ed25519::Signature,
+    commitments: BTreeMap<String, PedersenCommitment<constants::ArCurve>>,
+    /// The randomness of the commitments, which must be delivered to the
+    /// holder together with the values.
+    randomness: BTreeMap<String, PedersenRandomness<constants::ArCurve>>,
+}
+
+/// Commit to the attribute values of a credential with fresh randomness and
+/// sign the commitments as the issuer. The output, together with the values,
+/// holder and issuer, is accepted by `verify_web3_id_credential_signature_aux`.
+pub fn sign_web3_id_credential_aux(input: SignWeb3IdCredentialInput) -> Result<JsonString> {
+    let cmm_key = &input.global_context.on_chain_commitment_key;
+    let mut csprng = thread_rng();
+    let mut commitments = BTreeMap::new();
+    let mut randomness = BTreeMap::new();
+    for (name, value) in input.values.iter() {
+        let (commitment, r) = cmm_key.commit(
+            &PedersenValue::<constants::ArCurve>::new(value.to_field_element()),
+            &mut csprng,
+        );
+        commitments.insert(name.clone(), commitment);
+        randomness.insert(name.clone(), r);
+    }
+    let signed = SignedCommitments::from_commitments(
+        commitments,
+        &input.holder,
+        &input.issuer_key,
+        input.issuer_contract,
+    );
+    let output = SignedWeb3IdCredential {
+        signature: signed.signature,
+        commitments: signed.commitments,
+        randomness,
+    };
+    Ok(serde_json::to_string(&output)?)
+}
+
+/// Compute the digest that is signed when an account signs an arbitrary
+/// message. The account address and 8 zero bytes are prepended to the message
+/// to ensure that it can never be a valid transaction.
+fn message_digest(account: &AccountAddress, message: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(account.0);
+    hasher.update([0u8; 8]);
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Signatures on a message, indexed first by credential index and then by key
+/// index. This is the same layout as an account transaction signature.
+type MessageSignature = BTreeMap<u8, BTreeMap<u8, HexString>>;
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignMessageInput {
+    address: AccountAddress,
+    message: HexString,
+    keys: AccountKeys,
+}
+
+/// Sign the message with every key in the provided account keys.
+fn sign_message(address: &AccountAddress, message: &[u8], keys: &AccountKeys) -> MessageSignature {
+    let digest = message_digest(address, message);
+    keys.keys
+        .iter()
+        .map(|(cred_index, cred_keys)| {
+            let signatures = cred_keys
+                .keys
+                .iter()
+                .map(|(key_index, key_pair)| {
+                    (key_index.0, hex::encode(key_pair.sign(&digest).to_bytes()))
+                })
+                .collect();
+            (cred_index.index, signatures)
+        })
+        .collect()
+}
+
+/// Sign the given message with every key in the provided account keys.
+pub fn sign_message_aux(input: SignMessageInput) -> Result<JsonString> {
+    let message = hex::decode(&input.message)?;
+    let signature = sign_message(&input.address, &message, &input.keys);
+    Ok(serde_json::to_string(&signature)?)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountCredentialKeys {
+    credential_public_keys: CredentialPublicKeys,
+}
+
+#[derive(SerdeDeserialize)]
+struct AccountCredentialValue {
+    contents: AccountCredentialKeys,
+}
+
+/// A credential as it is returned as part of the account info. Only the public
+/// keys of the credential are needed to verify signatures.
+#[derive(SerdeDeserialize)]
+struct AccountInfoCredential {
+    value: AccountCredentialValue,
+}
+
+/// The parts of the account info that are needed to verify signatures
+/// produced by the account.
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountSignatureInfo {
+    account_address: AccountAddress,
+    account_threshold: u8,
+    account_credentials: BTreeMap<u8, AccountInfoCredential>,
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyMessageSignatureInput {
+    message: HexString,
+    signature: MessageSignature,
+    account_info: AccountSignatureInfo,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialSignatureReport {
+    threshold: u8,
+    /// Key indices with a valid signature.
+    valid_keys: Vec<u8>,
+    /// Key indices with a signature that does not verify.
+    invalid_keys: Vec<u8>,
+    /// Key indices that are not part of the credential.
+    unknown_keys: Vec<u8>,
+    threshold_met: bool,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSignatureReport {
+    /// Whether the signature is valid for the account, i.e. no signature is
+    /// invalid and enough credentials meet their threshold.
+    is_valid: bool,
+    account_threshold: u8,
+    credentials: BTreeMap<u8, CredentialSignatureReport>,
+    /// Credential indices that are not part of the account.
+    unknown_credentials: Vec<u8>,
+}
+
+fn verify_message_signature(
+    message: &[u8],
+    signature: &MessageSignature,
+    account: &AccountSignatureInfo,
+) -> MessageSignatureReport {
+    let digest = message_digest(&account.account_address, message);
+
+    let mut credentials = BTreeMap::new();
+    let mut unknown_credentials = Vec::new();
+    for (cred_index, cred_signature) in signature.iter() {
+        let Some(credential) = account.account_credentials.get(cred_index) else {
+            unknown_credentials.push(*cred_index);
+            continue;
+        };
+        let public_keys = &credential.value.contents.credential_public_keys;
+        let mut report = CredentialSignatureReport {
+            threshold: u8::from(public_keys.threshold),
+            valid_keys: Vec::new(),
+            invalid_keys: Vec::new(),
+            unknown_keys: Vec::new(),
+            threshold_met: false,
+        };
+        for (key_index, signature) in cred_signature.iter() {
+            let Some(key) = public_keys.keys.get(&KeyIndex(*key_index)) else {
+                report.unknown_keys.push(*key_index);
+                continue;
+            };
+            // Signatures which are not hex are invalid the same way as
+            // signatures of the wrong length are.
+            let valid =
+                hex::decode(signature).is_ok_and(|sig| key.verify(digest, &Signature { sig }));
+            if valid {
+                report.valid_keys.push(*key_index);
+            } else {
+                report.invalid_keys.push(*key_index);
+            }
+        }
+        report.threshold_met = report.valid_keys.len() >= usize::from(report.threshold);
+        credentials.insert(*cred_index, report);
+    }
+
+    let all_signatures_known = unknown_credentials.is_empty()
+        && credentials
+            .values()
+            .all(|c| c.invalid_keys.is_empty() && c.unknown_keys.is_empty());
+    let credentials_met = credentials.values().filter(|c| c.threshold_met).count();
+    MessageSignatureReport {
+        is_valid: all_signatures_known && credentials_met >= usize::from(account.account_threshold),
+        account_threshold: account.account_threshold,
+        credentials,
+        unknown_credentials,
+    }
+}
+
+/// Verify a signature on a message against the credential keys and thresholds
+/// of the account.
+pub fn verify_message_signature_aux(input: VerifyMessageSignatureInput) -> Result<JsonString> {
+    let message = hex::decode(&input.message)?;
+    let report = verify_message_signature(&message, &input.signature, &input.account_info);
+    Ok(serde_json::to_string(&report)?)
+}
+
+/// Verify a batch of message signatures, returning a report for each input in
+/// the same order.
+pub fn verify_message_signatures_aux(
+    inputs: Vec<VerifyMessageSignatureInput>,
+) -> Result<JsonString> {
+    let reports = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let message = hex::decode(&input.message)
+                .context(format!("Invalid message of signature {}", i))?;
+            Ok(verify_message_signature(
+                &message,
+                &input.signature,
+                &input.account_info,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(serde_json::to_string(&reports)?)
+}
+
+/// A structured message, i.e. a JSON value together with the schema type used
+/// to serialize it into the bytes that are signed.
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredMessage {
+    value: SerdeValue,
+    /// The hex-encoded schema type of the value.
+    schema: HexString,
+}
+
+impl StructuredMessage {
+    /// Serialize the value according to the schema, returning the canonical
+    /// message bytes together with a rendering of the value that is obtained
+    /// by deserializing those same bytes.
+    fn to_canonical(&self) -> Result<(Vec<u8>, SerdeValue)> {
+        let value_type: Type = contracts_common::from_bytes(&hex::decode(&self.schema)?)
+            .map_err(|_| anyhow!("Unable to parse schema type"))?;
+        let message = serialize_type_value(self.value.to_string(), &value_type, true)
+            .context("Unable to serialize value")?;
+        let rendered = deserialize_type_value(message.clone(), &value_type, true)
+            .context("Unable to deserialize value")?;
+        Ok((hex::decode(message)?, serde_json::from_str(&rendered)?))
+    }
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct StructuredMessageOutput {
+    /// The hex-encoded bytes of the serialized value.
+    message: HexString,
+    /// The hex-encoded digest that is signed by the account keys.
+    digest: HexString,
+    /// A human-readable rendering of the value for displaying to the user.
+    display: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<MessageSignature>,
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredMessageInput {
+    address: AccountAddress,
+    #[serde(flatten)]
+    message: StructuredMessage,
+    /// The keys to sign the message with. If not present, the message is only
+    /// prepared for signing.
+    keys: Option<AccountKeys>,
+}
+
+/// Produce the canonical bytes and digest of a structured message, together
+/// with a human-readable rendering of the value. If keys are provided, the
+/// message is also signed with every key.
+pub fn create_structured_message_aux(input: StructuredMessageInput) -> Result<JsonString> {
+    let (bytes, rendered) = input.message.to_canonical()?;
+    let output = StructuredMessageOutput {
+        digest: hex::encode(message_digest(&input.address, &bytes)),
+        display: serde_json::to_string_pretty(&rendered)?,
+        signature: input
+            .keys
+            .map(|keys| sign_message(&input.address, &bytes, &keys)),
+        message: hex::encode(bytes),
+    };
+    Ok(serde_json::to_string(&output)?)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyStructuredMessageSignatureInput {
+    #[serde(flatten)]
+    message: StructuredMessage,
+    signature: MessageSignature,
+    account_info: AccountSignatureInfo,
+}
+
+/// Verify a signature on a structured message. The value is serialized again
+/// using the schema, so the signature is only valid if it was produced on
+/// exactly the same bytes.
+pub fn verify_structured_message_signature_aux(
+    input: VerifyStructuredMessageSignatureInput,
+) -> Result<JsonString> {
+    let (bytes, _) = input.message.to_canonical()?;
+    let report = verify_message_signature(&bytes, &input.signature, &input.account_info);
+    Ok(serde_json::to_string(&report)?)
+}
+
+/// Size of the header of an account transaction: the sender (32 bytes), the
+/// nonce (8 bytes), the energy (8 bytes), the payload size (4 bytes) and the
+/// expiry (8 bytes).
+const ACCOUNT_TRANSACTION_HEADER_SIZE: u64 = 32 + 8 + 8 + 4 + 8;
+
+/// An exchange rate given as a fraction.
+#[derive(SerdeDeserialize)]
+pub struct ExchangeRateInput {
+    numerator: u64,
+    denominator: u64,
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnergyCostInput {
+    /// The hex-encoded serialized payload of the transaction.
+    payload: HexString,
+    signature_count: u32,
+    protocol_version: u64,
+    /// The number of credentials on the account before the transaction. Only
+    /// required for updating credentials or credential keys.
+    num_existing_credentials: Option<u16>,
+    /// The energy used by executing a smart contract. Only required for
+    /// initializing or updating contract instances.
+    execution_energy: Option<u64>,
+    euro_per_energy: Option<ExchangeRateInput>,
+    #[serde(rename = "microCCDPerEuro", alias = "microCcdPerEuro")]
+    micro_ccd_per_euro: Option<ExchangeRateInput>,
+}
+
+/// Check that the payload is a valid transaction in the given protocol
+/// version, and return the cost that is specific to the payload type.
+fn payload_specific_cost(
+    payload: &Payload,
+    protocol_version: u64,
+    num_existing_credentials: Option<u16>,
+    execution_energy: Option<u64>,
+) -> Result<Energy> {
+    let ensure_protocol = |supported: bool, name: &str| {
+        if supported {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "{} transactions are not supported in protocol version {}",
+                name,
+                protocol_version
+            ))
+        }
+    };
+    let num_existing_credentials =
+        || num_existing_credentials.context("The number of existing credentials is required");
+    let execution_energy = || {
+        execution_energy
+            .map(|energy| Energy { energy })
+            .context("The energy used by the contract execution is required")
+    };
+    let cost = match payload {
+        Payload::DeployModule { module } => cost::deploy_module(module.source.size()),
+        Payload::InitContract { .. } => execution_energy()?,
+        Payload::Update { .. } => execution_energy()?,
+        Payload::Transfer { .. } => cost::SIMPLE_TRANSFER,
+        Payload::AddBaker { .. } => {
+            ensure_protocol(protocol_version <= 3, "AddBaker")?;
+            cost::ADD_BAKER
+        }
+        Payload::RemoveBaker => {
+            ensure_protocol(protocol_version <= 3, "RemoveBaker")?;
+            cost::REMOVE_BAKER
+        }
+        Payload::UpdateBakerStake { .. } => {
+            ensure_protocol(protocol_version <= 3, "UpdateBakerStake")?;
+            cost::UPDATE_BAKER_STAKE
+        }
+        Payload::UpdateBakerRestakeEarnings { .. } => {
+            ensure_protocol(protocol_version <= 3, "UpdateBakerRestakeEarnings")?;
+            cost::UPDATE_BAKER_RESTAKE
+        }
+        Payload::UpdateBakerKeys { .. } => {
+            ensure_protocol(protocol_version <= 3, "UpdateBakerKeys")?;
+            cost::UPDATE_BAKER_KEYS
+        }
+        Payload::UpdateCredentialKeys { keys, .. } => {
+            cost::update_credential_keys(num_existing_credentials()?, keys.keys.len().try_into()?)
+        }
+        // Encrypted transfers are deprecated, but still need to be priced for
+        // the protocol versions which support them.
+        #[allow(deprecated)]
+        Payload::EncryptedAmountTransfer { .. } => {
+            ensure_protocol(protocol_version <= 6, "EncryptedAmountTransfer")?;
+            cost::ENCRYPTED_TRANSFER
+        }
+        #[allow(deprecated)]
+        Payload::TransferToEncrypted { .. } => {
+            ensure_protocol(protocol_version <= 6, "TransferToEncrypted")?;
+            cost::TRANSFER_TO_ENCRYPTED
+        }
+        Payload::TransferToPublic { .. } => cost::TRANSFER_TO_PUBLIC,
+        Payload::TransferWithSchedule { schedule, .. } => {
+            cost::scheduled_transfer(schedule.len().try_into()?)
+        }
+        Payload::UpdateCredentials { new_cred_infos, .. } => {
+            let num_keys = new_cred_infos
+                .values()
+                .map(|cdi| cdi.values.cred_key_info.keys.len().try_into())
+                .collect::<Result<Vec<u16>, _>>()?;
+            cost::update_credentials(num_existing_credentials()?, &num_keys)
+        }
+        Payload::RegisterData { .. } => cost::REGISTER_DATA,
+        Payload::TransferWithMemo { .. } => {
+            ensure_protocol(protocol_version >= 2, "TransferWithMemo")?;
+            cost::SIMPLE_TRANSFER
+        }
+        #[allow(deprecated)]
+        Payload::EncryptedAmountTransferWithMemo { .. } => {
+            ensure_protocol(
+                (2..=6).contains(&protocol_version),
+                "EncryptedAmountTransferWithMemo",
+            )?;
+            cost::ENCRYPTED_TRANSFER
+        }
+        Payload::TransferWithScheduleAndMemo { schedule, .. } => {
+            ensure_protocol(protocol_version >= 2, "TransferWithScheduleAndMemo")?;
+            cost::scheduled_transfer(schedule.len().try_into()?)
+        }
+        Payload::ConfigureBaker { data } => {
+            ensure_protocol(protocol_version >= 4, "ConfigureBaker")?;
+            if data.keys_with_proofs.is_some() {
+                cost::CONFIGURE_BAKER_WITH_KEYS
+            } else {
+                cost::CONFIGURE_BAKER_WITHOUT_KEYS
+            }
+        }
+        Payload::ConfigureDelegation { .. } => {
+            ensure_protocol(protocol_version >= 4, "ConfigureDelegation")?;
+            cost::CONFIGURE_DELEGATION
+        }
+        Payload::TokenUpdate { payload } => {
+            ensure_protocol(protocol_version >= 9, "TokenUpdate")?;
+            token_operations_cost(payload)?
+        }
+    };
+    Ok(cost)
+}
+
+/// The cost of the operations of a token update, the same as the cost the
+/// transaction builders of `concordium_base` assign to them.
+fn token_operations_cost(payload: &TokenOperationsPayload) -> Result<Energy> {
+    let operations = payload
+        .decode_operations()
+        .context("Unable to decode the token operations")?;
+    operations
+        .operations
+        .iter()
+        .try_fold(cost::PLT_OPERATIONS_TRANSACTIONS, |total, operation| {
+            let operation_cost = match operation {
+                Upward::Known(TokenOperation::Transfer(_)) => cost::PLT_TRANSFER,
+                Upward::Known(TokenOperation::Mint(_)) => cost::PLT_MINT,
+                Upward::Known(TokenOperation::Burn(_)) => cost::PLT_BURN,
+                Upward::Known(TokenOperation::AddAllowList(_))
+                | Upward::Known(TokenOperation::RemoveAllowList(_))
+                | Upward::Known(TokenOperation::AddDenyList(_))
+                | Upward::Known(TokenOperation::RemoveDenyList(_)) => cost::PLT_LIST_UPDATE,
+                Upward::Known(TokenOperation::Pause(_))
+                | Upward::Known(TokenOperation::Unpause(_)) => cost::PLT_PAUSE,
+                Upward::Unknown(_) => bail!("The cost of unknown token operations is not known"),
+            };
+            Ok(total + operation_cost)
+        })
+}
+
+/// Convert an amount of energy to microCCD using the given exchange rates.
+/// The result is rounded up, matching how the chain charges for energy.
+fn energy_to_micro_ccd(
+    energy: Energy,
+    euro_per_energy: &ExchangeRateInput,
+    micro_ccd_per_euro: &ExchangeRateInput,
+) -> Result<Amount> {
+    if euro_per_energy.denominator == 0 || micro_ccd_per_euro.denominator == 0 {
+        bail!("Exchange rates must have a non-zero denominator");
+    }
+    let numerator =
+        BigUint::from(energy.energy) * euro_per_energy.numerator * micro_ccd_per_euro.numerator;
+    let denominator = BigUint::from(euro_per_energy.denominator) * micro_ccd_per_euro.denominator;
+    let micro_ccd: u64 = ((numerator + &denominator - 1u32) / denominator)
+        .try_into()
+        .context("The cost in microCCD does not fit in an amount")?;
+    Ok(Amount::from_micro_ccd(micro_ccd))
+}
+
+/// Compute the energy cost of an account transaction with the given payload
+/// and number of signatures, and optionally its cost in microCCD.
+pub fn get_energy_cost_aux(input: EnergyCostInput) -> Result<JsonString> {
+    let payload_bytes = hex::decode(&input.payload)?;
+    let mut reader = payload_bytes.as_slice();
+    let payload: Payload =
+        from_bytes(&mut reader).context("Unable to deserialize the transaction payload")?;
+    ensure!(
+        reader.is_empty(),
+        "The transaction payload has {} trailing bytes",
+        reader.len()
+    );
+    let specific_cost = payload_specific_cost(
+        &payload,
+        input.protocol_version,
+        input.num_existing_credentials,
+        input.execution_energy,
+    )?;
+    let energy = cost::base_cost(
+        ACCOUNT_TRANSACTION_HEADER_SIZE + payload_bytes.len() as u64,
+        input.signature_count,
+    ) + specific_cost;
+
+    let micro_ccd = match (&input.euro_per_energy, &input.micro_ccd_per_euro) {
+        (Some(euro_per_energy), Some(micro_ccd_per_euro)) => Some(energy_to_micro_ccd(
+            energy,
+            euro_per_energy,
+            micro_ccd_per_euro,
+        )?),
+        (None, None) => None,
+        _ => bail!("Both euroPerEnergy and microCCDPerEuro must be provided to compute the cost in microCCD"),
+    };
+
+    Ok(json!({
+        "energy": energy.energy,
+        "microCcd": micro_ccd.map(|amount| amount.micro_ccd().to_string()),
+    })
+    .to_string())
+}
+
+/// Derive the secret key used to decrypt the encrypted (shielded) balance of
+/// the account created with the given credential counter.
+pub fn get_encryption_secret_key_aux(
+    seed_as_hex: HexString,
+    raw_net: &str,
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u8,
+    raw_global_context: &str,
+) -> Result<HexString> {
+    let wallet = wallet_from_hex(&seed_as_hex, raw_net)?;
+    encryption_secret_key_aux(
+        &wallet,
+        identity_provider_index,
+        identity_index,
+        credential_counter,
+        raw_global_context,
+    )
+}
+
+pub fn encryption_secret_key_aux(
+    wallet: &Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u8,
+    raw_global_context: &str,
+) -> Result<HexString> {
+    let global_context: GlobalContext<ArCurve> = from_str(raw_global_context)?;
+    let secret_key = encryption_secret_key(
+        wallet,
+        &global_context,
+        identity_provider_index,
+        identity_index,
+        credential_counter,
+    )?;
+    Ok(base16_encode_string(&secret_key))
+}
+
+fn encryption_secret_key(
+    wallet: &Wallet,
+    global_context: &GlobalContext<ArCurve>,
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u8,
+) -> Result<elgamal::SecretKey<ArCurve>> {
+    let scalar = wallet
+        .get_prf_key(identity_provider_index, identity_index)?
+        .prf_exponent(credential_counter)?;
+    Ok(elgamal::SecretKey {
+        generator: *global_context.elgamal_generator(),
+        scalar,
+    })
+}
+
+/// The default number of baby steps used for decryption tables.
+const DEFAULT_DECRYPTION_TABLE_SIZE: u64 = 1 << 16;
+
+/// Compute the baby-step giant-step table used for decrypting encrypted
+/// amounts.
+pub fn create_decryption_table_aux(
+    raw_global_context: &str,
+    size: Option<u64>,
+) -> Result<BabyStepGiantStep<ArCurve>> {
+    let global_context: GlobalContext<ArCurve> = from_str(raw_global_context)?;
+    Ok(BabyStepGiantStep::new(
+        global_context.encryption_in_exponent_generator(),
+        size.unwrap_or(DEFAULT_DECRYPTION_TABLE_SIZE),
+    ))
+}
+
+/// Decrypt each of the given hex-encoded encrypted amounts with the secret
+/// key, returning the amounts in microCCD.
+pub fn decrypt_amounts_aux(
+    table: &BabyStepGiantStep<ArCurve>,
+    secret_key: &str,
+    raw_encrypted_amounts: &str,
+) -> Result<JsonString> {
+    let secret_key: elgamal::SecretKey<ArCurve> =
+        base16_decode_string(secret_key).context("Invalid encryption secret key")?;
+    let encrypted_amounts: Vec<HexString> = from_str(raw_encrypted_amounts)?;
+    let amounts = encrypted_amounts
+        .iter()
+        .map(|amount| {
+            let amount: EncryptedAmount<ArCurve> =
+                base16_decode_string(amount).context("Invalid encrypted amount")?;
+            let decrypted = encrypted_transfers::decrypt_amount(table, &secret_key, &amount);
+            Ok(decrypted.micro_ccd().to_string())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(serde_json::to_string(&amounts)?)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferToPublicInput {
+    global_context: GlobalContext<ArCurve>,
+    /// The hex-encoded encryption secret key of the account.
+    secret_key: HexString,
+    /// The hex-encoded self amount of the account's encrypted balance.
+    self_amount: HexString,
+    /// The index of the first incoming amount in the account's encrypted
+    /// balance.
+    start_index: u64,
+    /// The hex-encoded incoming amounts of the account's encrypted balance.
+    incoming_amounts: Vec<HexString>,
+    /// The amount to transfer to the public balance.
+    amount: Amount,
+}
+
+/// Create the payload of a transfer from the encrypted balance to the public
+/// balance of an account, including the proofs required by the chain.
+pub fn create_transfer_to_public_aux(
+    table: &BabyStepGiantStep<ArCurve>,
+    input: TransferToPublicInput,
+) -> Result<JsonString> {
+    let secret_key: elgamal::SecretKey<ArCurve> =
+        base16_decode_string(&input.secret_key).context("Invalid encryption secret key")?;
+    let self_amount: EncryptedAmount<ArCurve> =
+        base16_decode_string(&input.self_amount).context("Invalid self amount")?;
+    // Encrypted transfers are deprecated since protocol version 7, but the
+    // existing encrypted balances can still be transferred to the public
+    // balance, which requires aggregating them.
+    #[allow(deprecated)]
+    let agg_encrypted_amount = input.incoming_amounts.iter().try_fold(
+        self_amount,
+        |agg_encrypted_amount, incoming| -> Result<_> {
+            let incoming: EncryptedAmount<ArCurve> =
+                base16_decode_string(incoming).context("Invalid incoming amount")?;
+            Ok(encrypted_transfers::aggregate(
+                &agg_encrypted_amount,
+                &incoming,
+            ))
+        },
+    )?;
+    let agg_amount = encrypted_transfers::decrypt_amount(table, &secret_key, &agg_encrypted_amount);
+    if agg_amount < input.amount {
+        bail!(
+            "Insufficient encrypted balance: {} microCCD available",
+            agg_amount.micro_ccd()
+        );
+    }
+    let agg_index = input.start_index + input.incoming_amounts.len() as u64;
+    let decrypted = AggregatedDecryptedAmount {
+        agg_encrypted_amount,
+        agg_amount,
+        agg_index: agg_index.into(),
+    };
+
+    let data = encrypted_transfers::make_sec_to_pub_transfer_data(
+        &input.global_context,
+        &secret_key,
+        &decrypted,
+        input.amount,
+        &mut thread_rng(),
+    )
+    .context("Unable to create the transfer proofs")?;
+    let payload = Payload::TransferToPublic {
+        data: Box::new(data),
+    };
+
+    Ok(json!({
+        "payload": payload,
+        "serializedPayload": hex::encode(to_bytes(&payload)),
+        "remainingAmount": (agg_amount - input.amount).micro_ccd().to_string(),
+    })
+    .to_string())
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityObjectVerificationInput {
+    ip_info: IpInfo<constants::IpPairing>,
+    ars_infos: BTreeMap<ArIdentity, ArInfo<constants::ArCurve>>,
+    global_context: GlobalContext<constants::ArCurve>,
+    id_object: IdentityObjectV1<constants::IpPairing, constants::ArCurve, AttributeKind>,
+    /// The time to check the validity of the identity object at.
+    verification_time: chrono::DateTime<chrono::Utc>,
+    /// The optional secrets of the identity, used to check the signature of
+    /// the identity provider on the commitments to them.
+    #[serde(flatten)]
+    secrets: Option<IdentitySecretsInput>,
+}
+
+/// The secrets of an identity, derived with `getIdCredSec`, `getPrfKey` and
+/// `getSignatureBlindingRandomness`.
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentitySecretsInput {
+    #[serde(deserialize_with = "base16_decode")]
+    id_cred_sec: <ArCurve as Curve>::Scalar,
+    #[serde(deserialize_with = "base16_decode")]
+    prf_key: prf::SecretKey<ArCurve>,
+    #[serde(deserialize_with = "base16_decode")]
+    blinding_randomness: SigRetrievalRandomness<constants::IpPairing>,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityObjectReport {
+    is_valid: bool,
+    /// Whether the proofs of the pre-identity object are valid for the
+    /// identity provider, the anonymity revokers and the global context, as
+    /// checked by the identity provider before signing it.
+    proofs_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proofs_error: Option<String>,
+    /// Whether the signature of the identity provider is valid. The identity
+    /// provider signs commitments to the secrets of the identity, so without
+    /// the secrets only the form of the signature is checked.
+    signature_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature_error: Option<String>,
+    /// Whether the signature was checked on the attribute list and the
+    /// secrets of the identity.
+    signature_verified: bool,
+    /// Whether the identity object has not expired yet.
+    not_expired: bool,
+    /// Whether the identity object was created in the past and before it
+    /// expires.
+    created_at_valid: bool,
+    /// Whether the anonymity revocation data matches the given anonymity
+    /// revokers.
+    ar_data_valid: bool,
+    ar_data_errors: Vec<String>,
+}
+
+/// Check the anonymity revocation data of the identity object against the
+/// known anonymity revokers, returning a description of each problem found.
+fn check_identity_ar_data(
+    id_object: &IdentityObjectV1<constants::IpPairing, constants::ArCurve, AttributeKind>,
+    ars_infos: &BTreeMap<ArIdentity, ArInfo<constants::ArCurve>>,
+) -> Vec<String> {
+    let pio = &id_object.pre_identity_object;
+    let ar_identities = &pio.choice_ar_parameters.ar_identities;
+    let mut errors = Vec::new();
+    for ar in ar_identities.iter() {
+        if !ars_infos.contains_key(ar) {
+            errors.push(format!("Anonymity revoker {} is not known", ar));
+        }
+        if !pio.ip_ar_data.contains_key(ar) {
+            errors.push(format!("Missing data for anonymity revoker {}", ar));
+        }
+    }
+    for ar in pio.ip_ar_data.keys() {
+        if !ar_identities.contains(ar) {
+            errors.push(format!(
+                "Data for anonymity revoker {} which was not chosen",
+                ar
+            ));
+        }
+    }
+    let threshold = pio.choice_ar_parameters.threshold;
+    if usize::from(threshold) > ar_identities.len() {
+        errors.push(format!(
+            "Invalid threshold {} for {} anonymity revokers",
+            threshold,
+            ar_identities.len()
+        ));
+    }
+    errors
+}
+
+/// Check the signature of the identity provider on the identity object. The
+/// identity provider signs commitments to the secrets of the identity, so the
+/// signature is checked on the message of the secrets and the public values,
+/// in the order of `identity_provider::compute_message`, after removing the
+/// blinding of the signature.
+fn check_identity_signature(
+    input: &IdentityObjectVerificationInput,
+    secrets: &IdentitySecretsInput,
+) -> Result<()> {
+    let pio = &input.id_object.pre_identity_object;
+    let alist = &input.id_object.alist;
+    let ar_parameters = &pio.choice_ar_parameters;
+
+    let mut message = vec![secrets.id_cred_sec, *secrets.prf_key];
+    message.push(
+        utils::encode_public_credential_values(
+            alist.created_at,
+            alist.valid_to,
+            ar_parameters.threshold,
+        )
+        .map_err(|e| anyhow!("Unable to encode the validity and threshold: {}", e))?,
+    );
+    message.extend(
+        utils::encode_ars::<<ArCurve as Curve>::Scalar>(&ar_parameters.ar_identities)
+            .context("Unable to encode the anonymity revokers")?,
+    );
+    message.push(
+        utils::encode_tags(alist.alist.keys())
+            .map_err(|e| anyhow!("Unable to encode the attribute tags: {}", e))?,
+    );
+    message.push(ArCurve::scalar_from_u64(alist.max_accounts.into()));
+    message.extend(alist.alist.values().map(|value| value.to_field_element()));
+
+    let signature = input
+        .id_object
+        .signature
+        .retrieve(&secrets.blinding_randomness);
+    ensure!(
+        input
+            .ip_info
+            .ip_verify_key
+            .verify(&signature, &ps_sig::KnownMessage(message)),
+        "The signature of the identity provider is not valid"
+    );
+    Ok(())
+}
+
+/// The chronological ordering key of a year and month, since [`YearMonth`]
+/// does not implement an ordering itself.
+fn year_month_key(year_month: YearMonth) -> (u16, u8) {
+    (year_month.year, year_month.month)
+}
+
+fn year_month_at(time: chrono::DateTime<chrono::Utc>) -> Result<YearMonth> {
+    Ok(YearMonth {
+        year: time.year().try_into()?,
+        month: time.month().try_into()?,
+    })
+}
+
+/// Verify an identity object offline against the identity provider, the
+/// anonymity revokers and the global context at the given time, producing a
+/// report of each of the checks. The signature of the identity provider is
+/// only verified if the secrets of the identity are given.
+pub fn verify_identity_object_aux(input: IdentityObjectVerificationInput) -> Result<JsonString> {
+    let current = year_month_at(input.verification_time)?;
+    let alist = &input.id_object.alist;
+    let not_expired = year_month_key(alist.valid_to) >= year_month_key(current);
+    let created_at_valid = year_month_key(alist.created_at) <= year_month_key(current)
+        && year_month_key(alist.created_at) <= year_month_key(alist.valid_to);
+
+    let ar_data_errors = check_identity_ar_data(&input.id_object, &input.ars_infos);
+    let context = IpContext {
+        ip_info: &input.ip_info,
+        ars_infos: &input.ars_infos,
+        global_context: &input.global_context,
+    };
+    let proofs_error =
+        identity_provider::validate_request_v1(&input.id_object.pre_identity_object, context)
+            .err()
+            .map(|e| e.to_string());
+    let signature_error = if input.id_object.signature.0.is_zero_point() {
+        Some("The signature of the identity provider is not valid".into())
+    } else if let Some(secrets) = &input.secrets {
+        check_identity_signature(&input, secrets)
+            .err()
+            .map(|e| e.to_string())
+    } else {
+        None
+    };
+
+    let report = IdentityObjectReport {
+        is_valid: proofs_error.is_none()
+            && signature_error.is_none()
+            && not_expired
+            && created_at_valid
+            && ar_data_errors.is_empty(),
+        proofs_valid: proofs_error.is_none(),
+        proofs_error,
+        signature_valid: signature_error.is_none(),
+        signature_error,
+        signature_verified: input.secrets.is_some(),
+        not_expired,
+        created_at_valid,
+        ar_data_valid: ar_data_errors.is_empty(),
+        ar_data_errors,
+    };
+    Ok(serde_json::to_string(&report)?)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialVerificationInput {
+    ip_info: IpInfo<constants::IpPairing>,
+    ars_infos: BTreeMap<ArIdentity, ArInfo<constants::ArCurve>>,
+    global_context: GlobalContext<constants::ArCurve>,
+    credential: CredentialDeploymentInfo<constants::IpPairing, constants::ArCurve, AttributeKind>,
+    /// The expiry of the transaction creating a new account with the
+    /// credential. Exactly one of `expiry` and `address` must be present.
+    expiry: Option<TransactionTime>,
+    /// The address of the existing account the credential is added to.
+    address: Option<AccountAddress>,
+}
+
+/// Verify a credential deployment the same way the chain does, checking the
+/// identity ownership proofs, the commitments, the anonymity revocation data
+/// and the signatures on the credential keys.
+pub fn verify_credential_deployment_aux(input: CredentialVerificationInput) -> Result<JsonString> {
+    let new_or_existing = match (input.expiry, input.address) {
+        (Some(expiry), None) => Left(expiry),
+        (None, Some(address)) => Right(address),
+        _ => bail!("Exactly one of expiry and address must be provided"),
+    };
+    let result = verify_cdi(
+        &input.global_context,
+        &input.ip_info,
+        &input.ars_infos,
+        &input.credential,
+        &new_or_existing,
+    );
+    let response = match result {
+        Ok(()) => json!({ "isValid": true }),
+        Err(e) => json!({ "isValid": false, "reason": cdi_verification_failure(e) }),
+    };
+    Ok(response.to_string())
+}
+
+/// The reason for a failed credential deployment verification.
+fn cdi_verification_failure(error: CdiVerificationError) -> &'static str {
+    match error {
+        CdiVerificationError::RegId => "The credential registration id is invalid",
+        CdiVerificationError::IdCredPub => {
+            "The encryptions of the shares of the credential holder's key are invalid"
+        }
+        CdiVerificationError::Signature => {
+            "The proof of the identity provider's signature is invalid"
+        }
+        CdiVerificationError::Dlog => "A proof of knowledge of a secret key is invalid",
+        CdiVerificationError::AccountOwnership => {
+            "The signatures of the keys of the credential are invalid"
+        }
+        CdiVerificationError::Policy => {
+            "The revealed attributes do not match the commitments to them"
+        }
+        CdiVerificationError::Ar => "The anonymity revocation data is invalid",
+        CdiVerificationError::Proof => "The proofs of the credential are invalid",
+    }
+}
+
+/// A range of consecutive derivation indices.
+#[derive(SerdeDeserialize)]
+pub struct DerivationRange {
+    start: u32,
+    count: u32,
+}
+
+impl DerivationRange {
+    fn indices(&self) -> Result<std::ops::Range<u32>> {
+        let end = self
+            .start
+            .checked_add(self.count)
+            .context("Derivation range out of bounds")?;
+        Ok(self.start..end)
+    }
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeriveCredentialsInput {
+    global_context: GlobalContext<constants::ArCurve>,
+    identity_provider_indices: Vec<u32>,
+    identity_indices: DerivationRange,
+    credential_counters: DerivationRange,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct DerivedCredential {
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u8,
+    cred_id: HexString,
+    public_key: HexString,
+    account_address: AccountAddress,
+}
+
+/// Derive the credential id, account signing public key and account address
+/// for every combination of identity provider index, identity index and
+/// credential counter in the given ranges. The keys of each identity are only
+/// derived once for all of its credentials.
+pub fn derive_credentials_aux(
+    wallet: &Wallet,
+    input: DeriveCredentialsInput,
+) -> Result<JsonString> {
+    let credential_counters = input.credential_counters.indices()?;
+    if credential_counters.end > u32::from(u8::MAX) + 1 {
+        bail!("Credential counters must be at most {}", u8::MAX);
+    }
+    let cmm_key = &input.global_context.on_chain_commitment_key;
+
+    let mut derived = Vec::new();
+    for &identity_provider_index in input.identity_provider_indices.iter() {
+        for identity_index in input.identity_indices.indices()? {
+            let prf_key = wallet.get_prf_key(identity_provider_index, identity_index)?;
+            for credential_counter in credential_counters.clone() {
+                // Checked to fit above.
+                let credential_counter = credential_counter as u8;
+                let cred_id_exponent = prf_key.prf_exponent(credential_counter)?;
+                let cred_id = cmm_key
+                    .hide(
+                        &PedersenValue::<constants::ArCurve>::new(cred_id_exponent),
+                        &PedersenRandomness::zero(),
+                    )
+                    .0;
+                let public_key = wallet.get_account_public_key(
+                    identity_provider_index,
+                    identity_index,
+                    u32::from(credential_counter),
+                )?;
+                derived.push(DerivedCredential {
+                    identity_provider_index,
+                    identity_index,
+                    credential_counter,
+                    cred_id: base16_encode_string(&cred_id),
+                    public_key: hex::encode(public_key.as_bytes()),
+                    account_address: account_address_from_registration_id(&cred_id),
+                });
+            }
+        }
+    }
+    Ok(serde_json::to_string(&derived)?)
+}
+
+/// Get the hex-encoded account signing key of the credential.
+pub fn account_signing_key_aux(
+    wallet: &Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u32,
+) -> Result<HexString> {
+    let key = wallet.get_account_signing_key(
+        identity_provider_index,
+        identity_index,
+        credential_counter,
+    )?;
+    Ok(hex::encode(key.to_bytes()))
+}
+
+/// Get the hex-encoded account public key of the credential.
+pub fn account_public_key_aux(
+    wallet: &Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u32,
+) -> Result<HexString> {
+    let key = wallet.get_account_public_key(
+        identity_provider_index,
+        identity_index,
+        credential_counter,
+    )?;
+    Ok(hex::encode(key.as_bytes()))
+}
+
+/// Get the hex-encoded credential id of the credential, given the hex-encoded
+/// on-chain commitment key.
+pub fn credential_id_aux(
+    wallet: &Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u8,
+    raw_on_chain_commitment_key: &str,
+) -> Result<HexString> {
+    let on_chain_commitment_key: PedersenKey<constants::ArCurve> =
+        base16_decode_string(raw_on_chain_commitment_key)
+            .context("Invalid on-chain commitment key")?;
+    let cred_id_exponent = wallet
+        .get_prf_key(identity_provider_index, identity_index)?
+        .prf_exponent(credential_counter)?;
+    let cred_id = on_chain_commitment_key
+        .hide(
+            &PedersenValue::<constants::ArCurve>::new(cred_id_exponent),
+            &PedersenRandomness::zero(),
+        )
+        .0;
+    Ok(base16_encode_string(&cred_id))
+}
+
+pub fn prf_key_aux(
+    wallet: &Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+) -> Result<HexString> {
+    let key = wallet.get_prf_key(identity_provider_index, identity_index)?;
+    Ok(base16_encode_string(&key))
+}
+
+pub fn id_cred_sec_aux(
+    wallet: &Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+) -> Result<HexString> {
+    let key = wallet.get_id_cred_sec(identity_provider_index, identity_index)?;
+    Ok(base16_encode_string(&key))
+}
+
+pub fn signature_blinding_randomness_aux(
+    wallet: &Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+) -> Result<HexString> {
+    let randomness = wallet.get_blinding_randomness(identity_provider_index, identity_index)?;
+    Ok(base16_encode_string(&randomness))
+}
+
+pub fn attribute_commitment_randomness_aux(
+    wallet: &Wallet,
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u32,
+    attribute: u8,
+) -> Result<HexString> {
+    let randomness = wallet.get_attribute_commitment_randomness(
+        identity_provider_index,
+        identity_index,
+        credential_counter,
+        AttributeTag(attribute),
+    )?;
+    Ok(base16_encode_string(&randomness))
+}
+
+pub fn verifiable_credential_signing_key_aux(
+    wallet: &Wallet,
+    issuer: ContractAddress,
+    verifiable_credential_index: u32,
+) -> Result<HexString> {
+    let key = wallet.get_verifiable_credential_signing_key(issuer, verifiable_credential_index)?;
+    Ok(hex::encode(key.to_bytes()))
+}
+
+pub fn verifiable_credential_public_key_aux(
+    wallet: &Wallet,
+    issuer: ContractAddress,
+    verifiable_credential_index: u32,
+) -> Result<HexString> {
+    let key = wallet.get_verifiable_credential_public_key(issuer, verifiable_credential_index)?;
+    Ok(hex::encode(key.as_bytes()))
+}
+
+pub fn verifiable_credential_backup_encryption_key_aux(wallet: &Wallet) -> Result<HexString> {
+    let key = wallet.get_verifiable_credential_backup_encryption_key()?;
+    Ok(hex::encode(key))
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignMessageWithCredentialKeyInput {
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u32,
+    key_index: u8,
+    address: AccountAddress,
+    /// The hex encoded message.
+    message: HexString,
+}
+
+/// Sign a message with the key with the given index of the credential,
+/// returning the hex-encoded signature.
+pub fn sign_message_with_credential_key_aux(
+    wallet: &Wallet,
+    input: SignMessageWithCredentialKeyInput,
+) -> Result<HexString> {
+    let key = wallet.get_credential_signing_key(
+        input.identity_provider_index,
+        input.identity_index,
+        input.credential_counter,
+        input.key_index,
+    )?;
+    let keypair = KeyPair::from(key);
+    let digest = message_digest(&input.address, &hex::decode(&input.message)?);
+    Ok(hex::encode(keypair.sign(&digest).to_bytes()))
+}
+
+/// The number of bytes of entropy of a mnemonic with the given number of
+/// words.
+fn mnemonic_entropy_size(word_count: usize) -> Result<usize> {
+    match word_count {
+        12 => Ok(16),
+        24 => Ok(32),
+        _ => bail!(
+            "Mnemonics must have either 12 or 24 words, got {}",
+            word_count
+        ),
+    }
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateMnemonicInput {
+    word_count: usize,
+    /// The entropy to create the mnemonic from. If not given, the entropy is
+    /// taken from the random number generator of the environment.
+    entropy: Option<HexString>,
+}
+
+/// Generate a BIP-39 mnemonic with the English word list.
+pub fn generate_mnemonic_aux(input: GenerateMnemonicInput) -> Result<String> {
+    let size = mnemonic_entropy_size(input.word_count)?;
+    let mut entropy = match input.entropy {
+        Some(entropy) => hex::decode(entropy).context("Invalid entropy")?,
+        None => {
+            let mut entropy = vec![0u8; size];
+            thread_rng().fill_bytes(&mut entropy);
+            entropy
+        }
+    };
+    ensure!(
+        entropy.len() == size,
+        "A mnemonic with {} words requires {} bytes of entropy, got {}",
+        input.word_count,
+        size,
+        entropy.len()
+    );
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy);
+    entropy.zeroize();
+    Ok(mnemonic?.to_string())
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MnemonicValidationReport {
+    is_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    /// The zero-based positions of the words that are not in the word list.
+    invalid_words: Vec<usize>,
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateMnemonicInput {
+    mnemonic: String,
+}
+
+/// Validate a BIP-39 mnemonic, reporting the positions of any words that are
+/// not in the English word list.
+pub fn validate_mnemonic_aux(input: ValidateMnemonicInput) -> Result<JsonString> {
+    let mnemonic = &input.mnemonic;
+    let invalid_words: Vec<usize> = mnemonic
+        .split_whitespace()
+        .enumerate()
+        .filter(|(_, word)| Language::English.find_word(word).is_none())
+        .map(|(position, _)| position)
+        .collect();
+    let reason = if invalid_words.is_empty() {
+        Mnemonic::parse_in(Language::English, mnemonic)
+            .err()
+            .map(|e| e.to_string())
+    } else {
+        Some(format!(
+            "The mnemonic contains {} words that are not in the word list",
+            invalid_words.len()
+        ))
+    };
+    let report = MnemonicValidationReport {
+        is_valid: reason.is_none(),
+        reason,
+        invalid_words,
+    };
+    Ok(serde_json::to_string(&report)?)
+}
+
+/// Compute the seed of a BIP-39 mnemonic and passphrase.
+pub fn mnemonic_to_seed(mnemonic: &str, passphrase: &str) -> Result<Zeroizing<[u8; 64]>> {
+    let mnemonic = Mnemonic::parse_in(Language::English, mnemonic).context("Invalid mnemonic")?;
+    Ok(Zeroizing::new(mnemonic.to_seed(passphrase)))
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MnemonicToSeedInput {
+    mnemonic: String,
+    #[serde(default)]
+    passphrase: String,
+}
+
+pub fn mnemonic_to_seed_aux(input: MnemonicToSeedInput) -> Result<HexString> {
+    let seed = mnemonic_to_seed(&input.mnemonic, &input.passphrase)?;
+    Ok(hex::encode(seed.as_slice()))
+}
+
+/// The account keys exported from a wallet, as read by `parseWallet` in the
+/// SDK.
+#[derive(SerdeSerialize, SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletExport {
+    #[serde(rename = "type")]
+    export_type: String,
+    v: u32,
+    environment: String,
+    value: WalletExportValue,
+}
+
+#[derive(SerdeSerialize, SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletExportValue {
+    address: AccountAddress,
+    credentials: BTreeMap<u8, HexString>,
+    account_keys: AccountKeys,
+}
+
+/// The export of a Concordium mobile wallet, of the type
+/// `concordium-mobile-wallet-data`, containing the identities of the wallet
+/// and the accounts created from each of them.
+#[derive(SerdeSerialize, SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MobileWalletExport {
+    #[serde(rename = "type")]
+    export_type: String,
+    v: u32,
+    environment: String,
+    value: MobileWalletExportValue,
+}
+
+/// The contents of a mobile wallet export. Only the accounts are interpreted,
+/// any other data of the export is kept as is.
+#[derive(SerdeSerialize, SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MobileWalletExportValue {
+    identities: Vec<MobileWalletIdentity>,
+    #[serde(flatten)]
+    other: serde_json::Map<String, SerdeValue>,
+}
+
+#[derive(SerdeSerialize, SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MobileWalletIdentity {
+    accounts: Vec<MobileWalletAccount>,
+    #[serde(flatten)]
+    other: serde_json::Map<String, SerdeValue>,
+}
+
+#[derive(SerdeSerialize, SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MobileWalletAccount {
+    address: AccountAddress,
+    account_keys: AccountKeys,
+    #[serde(flatten)]
+    other: serde_json::Map<String, SerdeValue>,
+}
+
+/// The type of the exports of the Concordium mobile wallets.
+const MOBILE_WALLET_EXPORT_TYPE: &str = "concordium-mobile-wallet-data";
+
+/// The contents of a password-encrypted wallet export, which is either the
+/// account keys of a single account or the export of a mobile wallet.
+#[derive(SerdeSerialize)]
+#[serde(untagged)]
+pub enum WalletExportContents {
+    Account(WalletExport),
+    Mobile(MobileWalletExport),
+}
+
+impl<'de> SerdeDeserialize<'de> for WalletExportContents {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let value = SerdeValue::deserialize(deserializer)?;
+        if value.get("type").and_then(SerdeValue::as_str) == Some(MOBILE_WALLET_EXPORT_TYPE) {
+            from_value(value)
+                .map(Self::Mobile)
+                .map_err(D::Error::custom)
+        } else {
+            from_value(value)
+                .map(Self::Account)
+                .map_err(D::Error::custom)
+        }
+    }
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptWalletExportInput {
+    export: EncryptedData,
+    password: String,
+}
+
+/// Decrypt a password-encrypted wallet export into the account keys it
+/// contains. Both the exports of a single account and the exports of the
+/// mobile wallets are supported.
+pub fn decrypt_wallet_export_aux(input: DecryptWalletExportInput) -> Result<JsonString> {
+    let password = Password::from(input.password);
+    let mut plaintext = encryption::decrypt(&password, &input.export).map_err(|_| {
+        anyhow!("Unable to decrypt the wallet export, the password may be incorrect")
+    })?;
+    let export: Result<WalletExportContents, _> = serde_json::from_slice(&plaintext);
+    plaintext.zeroize();
+    let export = export.context("The decrypted wallet export is malformed")?;
+    Ok(serde_json::to_string(&export)?)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptWalletExportInput {
+    export: WalletExportContents,
+    password: String,
+}
+
+/// Encrypt the account keys of a wallet export with a password, in the format
+/// of the wallet exports of the Concordium wallets.
+pub fn encrypt_wallet_export_aux(input: EncryptWalletExportInput) -> Result<JsonString> {
+    let password = Password::from(input.password);
+    let mut plaintext = serde_json::to_vec(&input.export)?;
+    let encrypted = encryption::encrypt(&password, &plaintext, &mut thread_rng());
+    plaintext.zeroize();
+    Ok(serde_json::to_string(&encrypted)?)
+}
+
+/// The version of the format of Web3 ID credential backups.
+const WEB3_ID_BACKUP_VERSION: u32 = 0;
+
+/// A Web3 ID credential in a backup, with the secrets needed to prove
+/// statements about it and any metadata the wallet keeps about it.
+#[derive(SerdeSerialize, SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Web3IdBackupCredential {
+    values: BTreeMap<String, Web3IdAttribute>,
+    randomness: BTreeMap<String, PedersenRandomness<constants::ArCurve>>,
+    #[serde(default)]
+    metadata: SerdeValue,
+}
+
+/// A backup of Web3 ID credentials encrypted with AES-256-GCM under the
+/// verifiable credential backup encryption key of the wallet.
+#[derive(SerdeSerialize, SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Web3IdBackup {
+    v: u32,
+    /// Identifies the key the backup is encrypted with, such that a backup
+    /// made with another seed can be told apart from a modified backup.
+    key_id: HexString,
+    nonce: HexString,
+    ciphertext: HexString,
+}
+
+/// The identifier of a backup encryption key, which is a truncated hash of
+/// the key.
+fn web3_id_backup_key_id(key: &[u8; 32]) -> [u8; 8] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"Web3IdBackupKeyId");
+    hasher.update(key);
+    let hash: [u8; 32] = hasher.finalize().into();
+    let mut key_id = [0u8; 8];
+    key_id.copy_from_slice(&hash[..8]);
+    key_id
+}
+
+/// The data authenticated together with the encrypted credentials.
+fn web3_id_backup_associated_data(version: u32, key_id: &[u8]) -> Vec<u8> {
+    let mut aad = b"Web3IdBackup".to_vec();
+    aad.extend_from_slice(&version.to_be_bytes());
+    aad.extend_from_slice(key_id);
+    aad
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptWeb3IdBackupInput {
+    credentials: Vec<Web3IdBackupCredential>,
+}
+
+/// Encrypt Web3 ID credentials under the backup encryption key of the wallet.
+pub fn encrypt_web3_id_backup_aux(
+    wallet: &Wallet,
+    input: EncryptWeb3IdBackupInput,
+) -> Result<JsonString> {
+    let key = wallet.get_verifiable_credential_backup_encryption_key()?;
+    let key_id = web3_id_backup_key_id(&key);
+    let cipher = Aes256Gcm::new_from_slice(key.as_slice())
+        .map_err(|_| anyhow!("Invalid backup encryption key"))?;
+
+    let mut nonce = [0u8; 12];
+    thread_rng().fill_bytes(&mut nonce);
+    let mut plaintext = serde_json::to_vec(&input.credentials)?;
+    let aad = web3_id_backup_associated_data(WEB3_ID_BACKUP_VERSION, &key_id);
+    let ciphertext = cipher.encrypt(
+        Nonce::from_slice(&nonce),
+        AeadPayload {
+            msg: &plaintext,
+            aad: &aad,
+        },
+    );
+    plaintext.zeroize();
+    let ciphertext = ciphertext.map_err(|_| anyhow!("Unable to encrypt the backup"))?;
+
+    let backup = Web3IdBackup {
+        v: WEB3_ID_BACKUP_VERSION,
+        key_id: hex::encode(key_id),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    };
+    Ok(serde_json::to_string(&backup)?)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptWeb3IdBackupInput {
+    backup: Web3IdBackup,
+}
+
+/// Decrypt a backup of Web3 ID credentials made with
+/// `encrypt_web3_id_backup_aux`, failing if the backup was made with another
+/// seed or has been modified.
+pub fn decrypt_web3_id_backup_aux(
+    wallet: &Wallet,
+    input: DecryptWeb3IdBackupInput,
+) -> Result<JsonString> {
+    let backup = input.backup;
+    ensure!(
+        backup.v == WEB3_ID_BACKUP_VERSION,
+        "Unsupported backup version {}",
+        backup.v
+    );
+    let key = wallet.get_verifiable_credential_backup_encryption_key()?;
+    let key_id = web3_id_backup_key_id(&key);
+    let cipher = Aes256Gcm::new_from_slice(key.as_slice())
+        .map_err(|_| anyhow!("Invalid backup encryption key"))?;
+    ensure!(
+        hex::decode(&backup.key_id).context("Invalid key id")? == key_id,
+        "The backup was made with a different seed"
+    );
+
+    let nonce: [u8; 12] = hex::decode(&backup.nonce)
+        .context("Invalid nonce")?
+        .try_into()
+        .map_err(|_| anyhow!("The nonce must be 12 bytes"))?;
+    let ciphertext = hex::decode(&backup.ciphertext).context("Invalid ciphertext")?;
+    let aad = web3_id_backup_associated_data(backup.v, &key_id);
+    let mut plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            AeadPayload {
+                msg: &ciphertext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| anyhow!("The backup has been modified"))?;
+    let credentials: Result<Vec<Web3IdBackupCredential>, _> = serde_json::from_slice(&plaintext);
+    plaintext.zeroize();
+    let credentials = credentials.context("The decrypted backup is malformed")?;
+    Ok(serde_json::to_string(&credentials)?)
+}
+
+/// A candidate for the private inputs of a subject claim. The credential id
+/// of an account credential is not part of its private inputs, so it is given
+/// alongside them for matching the candidate against account based claims.
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+struct SatisfiabilityCandidate {
+    #[serde(default)]
+    cred_id: Option<CredentialRegistrationID>,
+    inputs: OwnedCredentialProofPrivateInputs<constants::IpPairing, ArCurve, Web3IdAttribute>,
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationSatisfiabilityInput {
+    request: RequestV1<ArCurve, Web3IdAttribute>,
+    candidates: Vec<SatisfiabilityCandidate>,
+    /// The time to check the validity of identity credentials at.
+    verification_time: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct StatementSatisfiability {
+    #[serde(rename = "type")]
+    statement_type: &'static str,
+    attribute_tag: AttributeTag,
+    satisfied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct CandidateSatisfiability {
+    candidate_index: usize,
+    satisfied: bool,
+    /// Why the candidate cannot be used for the claims at all, e.g. because
+    /// it is another type of credential, from another issuer or expired.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    statements: Vec<StatementSatisfiability>,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct ClaimSatisfiability {
+    satisfiable: bool,
+    candidates: Vec<CandidateSatisfiability>,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct PresentationSatisfiabilityReport {
+    satisfiable: bool,
+    subject_claims: Vec<ClaimSatisfiability>,
+}
+
+/// The embedding of an attribute value as a field element, which is what the
+/// proofs are about. The embedding is serialized big-endian, so comparing the
+/// bytes compares the values the way range proofs do.
+fn embedded_attribute(value: &Web3IdAttribute) -> Vec<u8> {
+    to_bytes(&value.to_field_element())
+}
+
+/// Check whether the attributes satisfy the statement, returning the reason
+/// if they do not.
+fn check_statement(
+    statement: &AtomicStatementV1<ArCurve, AttributeTag, Web3IdAttribute>,
+    attributes: &BTreeMap<AttributeTag, Web3IdAttribute>,
+) -> Option<String> {
+    let Some(value) = attributes.get(&statement.attribute()) else {
+        return Some(format!(
+            "The credential does not have the attribute {}",
+            statement.attribute()
+        ));
+    };
+    let value = embedded_attribute(value);
+    let in_set = |set: &std::collections::BTreeSet<Web3IdAttribute>| {
+        set.iter().any(|v| embedded_attribute(v) == value)
+    };
+    match statement {
+        AtomicStatementV1::AttributeValue(statement) => (value
+            != embedded_attribute(&statement.attribute_value))
+        .then(|| "The attribute is not equal to the requested value".into()),
+        AtomicStatementV1::AttributeInSet(statement) => {
+            (!in_set(&statement.set)).then(|| "The attribute is not in the set".into())
+        }
+        AtomicStatementV1::AttributeNotInSet(statement) => {
+            in_set(&statement.set).then(|| "The attribute is in the set".into())
+        }
+        AtomicStatementV1::AttributeInRange(statement) => (value
+            < embedded_attribute(&statement.lower)
+            || value >= embedded_attribute(&statement.upper))
+        .then(|| {
+            "The attribute is not in the range from the lower bound (inclusive) to the upper \
+             bound (exclusive)"
+                .into()
+        }),
+    }
+}
+
+fn statement_type_name(
+    statement: &AtomicStatementV1<ArCurve, AttributeTag, Web3IdAttribute>,
+) -> &'static str {
+    match statement {
+        AtomicStatementV1::AttributeValue(_) => "AttributeValue",
+        AtomicStatementV1::AttributeInRange(_) => "AttributeInRange",
+        AtomicStatementV1::AttributeInSet(_) => "AttributeInSet",
+        AtomicStatementV1::AttributeNotInSet(_) => "AttributeNotInSet",
+    }
+}
+
+fn check_candidate(
+    claims: &SubjectClaims<ArCurve, Web3IdAttribute>,
+    candidate_index: usize,
+    candidate: &SatisfiabilityCandidate,
+    current: YearMonth,
+) -> CandidateSatisfiability {
+    let other_issuer = |required: IpIdentity, actual: IpIdentity| {
+        Some(format!(
+            "The claims require identity provider {}, but the candidate is from identity provider \
+             {}",
+            required, actual
+        ))
+    };
+    let matched = match (claims, &candidate.inputs) {
+        (SubjectClaims::Account(claims), OwnedCredentialProofPrivateInputs::Account(inputs)) => {
+            let reason = if claims.issuer != inputs.issuer {
+                other_issuer(claims.issuer, inputs.issuer)
+            } else if candidate.cred_id.as_ref() != Some(&claims.cred_id) {
+                Some(format!(
+                    "The claims are about the account credential {}, but the candidate is not",
+                    claims.cred_id
+                ))
+            } else {
+                None
+            };
+            Ok((&claims.statements, &inputs.attribute_values, reason))
+        }
+        (SubjectClaims::Identity(claims), OwnedCredentialProofPrivateInputs::Identity(inputs)) => {
+            let alist = &inputs.id_object.alist;
+            let reason = if claims.issuer != inputs.ip_info.ip_identity {
+                other_issuer(claims.issuer, inputs.ip_info.ip_identity)
+            } else if year_month_key(alist.created_at) > year_month_key(current) {
+                Some(format!(
+                    "The identity is not valid until {}",
+                    alist.created_at
+                ))
+            } else if year_month_key(alist.valid_to) < year_month_key(current) {
+                Some(format!("The identity expired after {}", alist.valid_to))
+            } else {
+                None
+            };
+            Ok((&claims.statements, &alist.alist, reason))
+        }
+        (SubjectClaims::Account(_), _) => Err(
+            "The claims are about an account credential, but the candidate is an identity \
+             credential",
+        ),
+        (SubjectClaims::Identity(_), _) => Err(
+            "The claims are about an identity credential, but the candidate is an account \
+             credential",
+        ),
+    };
+    let (statements, reason) = match matched {
+        Ok((statements, attributes, None)) => {
+            let statements: Vec<StatementSatisfiability> = statements
+                .iter()
+                .map(|statement| {
+                    let reason = check_statement(statement, attributes);
+                    StatementSatisfiability {
+                        statement_type: statement_type_name(statement),
+                        attribute_tag: statement.attribute(),
+                        satisfied: reason.is_none(),
+                        reason,
+                    }
+                })
+                .collect();
+            (statements, None)
+        }
+        Ok((_, _, reason)) => (Vec::new(), reason),
+        Err(reason) => (Vec::new(), Some(reason.into())),
+    };
+    CandidateSatisfiability {
+        candidate_index,
+        satisfied: reason.is_none() && statements.iter().all(|s| s.satisfied),
+        reason,
+        statements,
+    }
+}
+
+/// Check which of the candidate private inputs satisfy the statements of each
+/// subject claim of a presentation request, without creating any proofs. The
+/// report gives the reason for every statement that is not satisfied, and
+/// identity credentials are checked to be valid at the verification time.
+pub fn check_presentation_satisfiability_aux(
+    input: PresentationSatisfiabilityInput,
+) -> Result<JsonString> {
+    let current = year_month_at(input.verification_time)?;
+    let subject_claims: Vec<ClaimSatisfiability> = input
+        .request
+        .subject_claims
+        .iter()
+        .map(|claims| {
+            let candidates: Vec<CandidateSatisfiability> = input
+                .candidates
+                .iter()
+                .enumerate()
+                .map(|(index, candidate)| check_candidate(claims, index, candidate, current))
+                .collect();
+            ClaimSatisfiability {
+                satisfiable: candidates.iter().any(|c| c.satisfied),
+                candidates,
+            }
+        })
+        .collect();
+    let report = PresentationSatisfiabilityReport {
+        satisfiable: subject_claims.iter().all(|c| c.satisfiable),
+        subject_claims,
+    };
+    Ok(serde_json::to_string(&report)?)
+}
+
+/// The attributes which may be used in range statements, which are all dates
+/// of the form `YYYYMMDD`.
+const DATE_ATTRIBUTES: [&str; 3] = ["dob", "idDocIssuedAt", "idDocExpiresAt"];
+
+#[derive(SerdeSerialize)]
+struct StatementProblem {
+    path: String,
+    message: String,
+}
+
+/// The parts of an atomic statement which are validated. The values of
+/// identity statements are given as [`Web3IdAttribute::String`]s, which are
+/// embedded the same way as the [`AttributeKind`]s they hold.
+enum StatementParts {
+    Reveal,
+    Value,
+    Set(Vec<Web3IdAttribute>),
+    Range(Web3IdAttribute, Web3IdAttribute),
+}
+
+/// An atomic statement of the SDK which can be validated.
+trait ValidatedStatement: serde::de::DeserializeOwned {
+    fn attribute_tag(&self) -> AttributeTag;
+
+    fn parts(&self) -> StatementParts;
+}
+
+impl ValidatedStatement for AtomicStatement<ArCurve, AttributeTag, AttributeKind> {
+    fn attribute_tag(&self) -> AttributeTag {
+        self.attribute()
+    }
+
+    fn parts(&self) -> StatementParts {
+        let value = |v: &AttributeKind| Web3IdAttribute::String(v.clone());
+        match self {
+            AtomicStatement::RevealAttribute { .. } => StatementParts::Reveal,
+            AtomicStatement::AttributeInRange { statement } => {
+                StatementParts::Range(value(&statement.lower), value(&statement.upper))
+            }
+            AtomicStatement::AttributeInSet { statement } => {
+                StatementParts::Set(statement.set.iter().map(value).collect())
+            }
+            AtomicStatement::AttributeNotInSet { statement } => {
+                StatementParts::Set(statement.set.iter().map(value).collect())
+            }
+        }
+    }
+}
+
+impl ValidatedStatement for AtomicStatementV1<ArCurve, AttributeTag, Web3IdAttribute> {
+    fn attribute_tag(&self) -> AttributeTag {
+        self.attribute()
+    }
+
+    fn parts(&self) -> StatementParts {
+        match self {
+            AtomicStatementV1::AttributeValue(_) => StatementParts::Value,
+            AtomicStatementV1::AttributeInRange(statement) => {
+                StatementParts::Range(statement.lower.clone(), statement.upper.clone())
+            }
+            AtomicStatementV1::AttributeInSet(statement) => {
+                StatementParts::Set(statement.set.iter().cloned().collect())
+            }
+            AtomicStatementV1::AttributeNotInSet(statement) => {
+                StatementParts::Set(statement.set.iter().cloned().collect())
+            }
+        }
+    }
+}
+
+/// Whether two attribute values are of the same type, e.g. both strings.
+fn same_type(a: &Web3IdAttribute, b: &Web3IdAttribute) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+/// Validate the atomic statements at `path`, adding any problems found. Each
+/// statement is read as the typed statement `S` of the SDK, and they are
+/// returned, with the sets sorted and without duplicates, if they all are.
+fn validate_statements<S: ValidatedStatement>(
+    statements: &[SerdeValue],
+    path: &str,
+    problems: &mut Vec<StatementProblem>,
+) -> Option<Vec<S>> {
+    let mut problem = |index: Option<usize>, message: String| {
+        problems.push(StatementProblem {
+            path: match index {
+                Some(index) => format!("{}[{}]", path, index),
+                None => path.into(),
+            },
+            message,
+        })
+    };
+    if statements.is_empty() {
+        problem(None, "Empty statements are not allowed".into());
+    }
+    let mut normalized = Vec::new();
+    let mut revealed = BTreeMap::<AttributeTag, bool>::new();
+    let mut well_formed = true;
+    for (index, statement) in statements.iter().enumerate() {
+        let statement: S = match from_value(statement.clone()) {
+            Ok(statement) => statement,
+            Err(e) => {
+                problem(Some(index), format!("Malformed statement: {}", e));
+                well_formed = false;
+                continue;
+            }
+        };
+        let tag = statement.attribute_tag().to_string();
+        if !ATTRIBUTE_NAMES.contains(&tag.as_str()) {
+            problem(Some(index), format!("Unknown attribute tag {}", tag));
+        }
+        let parts = statement.parts();
+        let is_reveal = matches!(parts, StatementParts::Reveal);
+        match revealed.get(&statement.attribute_tag()) {
+            Some(&previous) if previous != is_reveal => problem(
+                Some(index),
+                format!("The attribute {} is both revealed and proven", tag),
+            ),
+            Some(_) => problem(
+                Some(index),
+                format!("Only one statement is allowed for the attribute {}", tag),
+            ),
+            None => {
+                revealed.insert(statement.attribute_tag(), is_reveal);
+            }
+        }
+        match parts {
+            StatementParts::Set(set) => {
+                if set.is_empty() {
+                    problem(Some(index), "Sets may not be empty".into());
+                }
+                if set.iter().any(|value| !same_type(value, &set[0])) {
+                    problem(
+                        Some(index),
+                        "The set contains values of different types".into(),
+                    );
+                }
+            }
+            StatementParts::Range(lower, upper) => {
+                if DATE_ATTRIBUTES.contains(&tag.as_str()) {
+                    for (name, bound) in [("lower", &lower), ("upper", &upper)] {
+                        let is_date = matches!(bound, Web3IdAttribute::String(date)
+                            if date.as_ref().len() == 8
+                                && chrono::NaiveDate::parse_from_str(date.as_ref(), "%Y%m%d")
+                                    .is_ok());
+                        if !is_date {
+                            problem(
+                                Some(index),
+                                format!("The {} bound of {} must be a date YYYYMMDD", name, tag),
+                            );
+                        }
+                    }
+                } else if ATTRIBUTE_NAMES.contains(&tag.as_str()) {
+                    problem(
+                        Some(index),
+                        format!("{} is not allowed to be used in range statements", tag),
+                    );
+                }
+                if !same_type(&lower, &upper) {
+                    problem(
+                        Some(index),
+                        "The bounds of the range have different types".into(),
+                    );
+                } else if embedded_attribute(&lower) >= embedded_attribute(&upper) {
+                    problem(
+                        Some(index),
+                        "The lower bound must be less than the upper bound".into(),
+                    );
+                }
+            }
+            StatementParts::Reveal | StatementParts::Value => (),
+        }
+        normalized.push(statement);
+    }
+    well_formed.then_some(normalized)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateStatementsInput {
+    /// An identity statement, as used for `create_id_proof_aux`.
+    statement: Option<Vec<SerdeValue>>,
+    /// The subject claims of a `RequestV1`.
+    subject_claims: Option<Vec<SerdeValue>>,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct ValidateStatementsOutput {
+    is_valid: bool,
+    problems: Vec<StatementProblem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    statement: Option<Statement<ArCurve, AttributeKind>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subject_claims: Option<Vec<SubjectClaims<ArCurve, Web3IdAttribute>>>,
+}
+
+/// Validate an identity statement and/or the subject claims of a `RequestV1`,
+/// returning all problems found. Values are ordered the way the prover
+/// orders them, so a range is only valid if its lower bound is less than its
+/// upper bound under that ordering. If there are no problems, the statements
+/// are also returned normalized, as the typed `Statement` and `SubjectClaims`
+/// of the SDK.
+pub fn validate_statements_aux(input: ValidateStatementsInput) -> Result<JsonString> {
+    let mut problems = Vec::new();
+    let statement = input.statement.map(|statement| {
+        validate_statements(&statement, "statement", &mut problems)
+            .map(|statements| Statement { statements })
+    });
+    let subject_claims = input.subject_claims.map(|claims| {
+        claims
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut claims)| {
+                let path = format!("subjectClaims[{}].statement", index);
+                let statements = match claims.get("statement").and_then(SerdeValue::as_array) {
+                    Some(statements) => validate_statements::<
+                        AtomicStatementV1<ArCurve, AttributeTag, Web3IdAttribute>,
+                    >(statements, &path, &mut problems),
+                    None => {
+                        problems.push(StatementProblem {
+                            path,
+                            message: "Missing statements".into(),
+                        });
+                        None
+                    }
+                };
+                claims["statement"] = json!(statements?);
+                match from_value(claims) {
+                    Ok(claims) => Some(claims),
+                    Err(e) => {
+                        problems.push(StatementProblem {
+                            path: format!("subjectClaims[{}]", index),
+                            message: format!("Malformed subject claims: {}", e),
+                        });
+                        None
+                    }
+                }
+            })
+            // Validate all the claims before giving up on normalizing them.
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+    });
+    let is_valid = problems.is_empty();
+    let out = ValidateStatementsOutput {
+        is_valid,
+        problems,
+        statement: statement.flatten().filter(|_| is_valid),
+        subject_claims: subject_claims.flatten().filter(|_| is_valid),
+    };
+    Ok(serde_json::to_string(&out)?)
+}
+
+/// The status of a credential, e.g. as read from the registry of its issuer.
+#[derive(SerdeSerialize, SerdeDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialStatusInput {
+    Active,
+    Revoked,
+    Expired,
+    NotActivated,
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyPresentationPolicyInput {
+    presentation: PresentationV1<constants::IpPairing, ArCurve, Web3IdAttribute>,
+    /// The request the presentation must prove.
+    request: RequestV1<ArCurve, Web3IdAttribute>,
+    global_context: GlobalContext<ArCurve>,
+    /// The verification material of each credential of the presentation.
+    public_data: Vec<CredentialVerificationMaterial<constants::IpPairing, ArCurve>>,
+    /// The time to check the validity of the credentials at.
+    verification_time: chrono::DateTime<chrono::Utc>,
+    /// The DIDs of the issuers whose credentials are accepted.
+    #[serde(default)]
+    trusted_issuers: Vec<String>,
+    /// The DIDs of the identity providers whose credentials are accepted,
+    /// e.g. `did:ccd:mainnet:idp:0`.
+    #[serde(default)]
+    trusted_identity_providers: Vec<IdentityProviderDid>,
+    /// The status of each credential of the presentation.
+    statuses: Vec<CredentialStatusInput>,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct StatementVerdict {
+    is_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct CredentialVerdict {
+    is_valid: bool,
+    issuer: Option<String>,
+    status: Option<CredentialStatusInput>,
+    reasons: Vec<String>,
+    statements: Vec<StatementVerdict>,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct PresentationVerdict {
+    is_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    credentials: Vec<CredentialVerdict>,
+}
+
+/// The network, issuer and statements of subject claims.
+fn claims_parts(
+    claims: &SubjectClaims<ArCurve, Web3IdAttribute>,
+) -> (
+    Network,
+    IpIdentity,
+    &[AtomicStatementV1<ArCurve, AttributeTag, Web3IdAttribute>],
+) {
+    match claims {
+        SubjectClaims::Account(claims) => (claims.network, claims.issuer, &claims.statements),
+        SubjectClaims::Identity(claims) => (claims.network, claims.issuer, &claims.statements),
+    }
+}
+
+/// Check whether the claims of a credential are about the requested subject,
+/// i.e. the same kind of credential on the same network from the same issuer,
+/// and for account based claims the same account credential.
+fn check_claims_subject(
+    requested: &SubjectClaims<ArCurve, Web3IdAttribute>,
+    proven: &SubjectClaims<ArCurve, Web3IdAttribute>,
+) -> Option<String> {
+    match (requested, proven) {
+        (SubjectClaims::Account(requested), SubjectClaims::Account(proven))
+            if requested.cred_id != proven.cred_id =>
+        {
+            Some(format!(
+                "The credential is for the account credential {}, but {} was requested",
+                proven.cred_id, requested.cred_id
+            ))
+        }
+        (SubjectClaims::Account(_), SubjectClaims::Account(_))
+        | (SubjectClaims::Identity(_), SubjectClaims::Identity(_)) => {
+            let (requested_network, requested_issuer, _) = claims_parts(requested);
+            let (network, issuer, _) = claims_parts(proven);
+            (requested_network != network || requested_issuer != issuer).then(|| {
+                format!(
+                    "The credential is issued by {}, but {} was requested",
+                    did::Method::new_idp(network, issuer),
+                    did::Method::new_idp(requested_network, requested_issuer)
+                )
+            })
+        }
+        (SubjectClaims::Account(_), SubjectClaims::Identity(_)) => Some(
+            "An account based credential was requested, but the credential is identity based"
+                .into(),
+        ),
+        (SubjectClaims::Identity(_), SubjectClaims::Account(_)) => Some(
+            "An identity based credential was requested, but the credential is account based"
+                .into(),
+        ),
+    }
+}
+
+/// Check the validity window of an identity based credential in the given
+/// month. Account based credentials carry no validity window, their validity
+/// is reflected in their status instead.
+fn check_validity_window(validity: &CredentialValidity, month: YearMonth) -> Option<String> {
+    if year_month_key(month) < year_month_key(validity.created_at) {
+        Some(format!(
+            "The credential is not valid until {}",
+            validity.created_at
+        ))
+    } else if year_month_key(month) > year_month_key(validity.valid_to) {
+        Some(format!(
+            "The credential expired after {}",
+            validity.valid_to
+        ))
+    } else {
+        None
+    }
+}
+
+/// Verify a presentation against the request it must prove, and check the
+/// validity window, issuer and status of each of its credentials. The
+/// verdict gives the reasons for each credential and statement which is not
+/// valid.
+pub fn verify_presentation_policy_aux(input: VerifyPresentationPolicyInput) -> Result<JsonString> {
+    ensure!(
+        input.statuses.len() == input.public_data.len(),
+        "Expected a status for each of the {} credentials, got {}",
+        input.public_data.len(),
+        input.statuses.len()
+    );
+    let verification_month = year_month_at(input.verification_time)?;
+    let verified = input
+        .presentation
+        .verify(&input.global_context, input.public_data.iter());
+    let (proven_request, proof_error) = match verified {
+        Ok(request) => (Some(request), None),
+        Err(e) => (None, Some(format!("The proofs are invalid: {}", e))),
+    };
+    let context_matches = proven_request
+        .as_ref()
+        .is_none_or(|r| r.context == input.request.context);
+
+    let presented = &input.presentation.verifiable_credentials;
+    let requested = &input.request.subject_claims;
+    let mut credentials = Vec::new();
+    for index in 0..presented.len().max(requested.len()) {
+        let expected_claims = requested.get(index);
+        let expected_statements = expected_claims.map_or(&[][..], |c| claims_parts(c).2);
+        let Some(credential) = presented.get(index) else {
+            credentials.push(CredentialVerdict {
+                is_valid: false,
+                issuer: None,
+                status: None,
+                reasons: vec!["The presentation has no credential for the requested claims".into()],
+                statements: expected_statements
+                    .iter()
+                    .map(|_| StatementVerdict {
+                        is_valid: false,
+                        reason: Some("The statement was not proven".into()),
+                    })
+                    .collect(),
+            });
+            continue;
+        };
+        let claims = credential.claims();
+        let (network, issuer, proven_statements) = claims_parts(&claims);
+        let status = input.statuses.get(index).copied();
+
+        let mut reasons = Vec::new();
+        match status {
+            Some(CredentialStatusInput::Active) => (),
+            Some(status) => reasons.push(format!(
+                "The credential is {}",
+                match status {
+                    CredentialStatusInput::Active => "active",
+                    CredentialStatusInput::Revoked => "revoked",
+                    CredentialStatusInput::Expired => "expired",
+                    CredentialStatusInput::NotActivated => "not activated",
+                }
+            )),
+            None => reasons.push("The status of the credential is unknown".into()),
+        }
+        if let CredentialV1::Identity(credential) = credential {
+            reasons.extend(check_validity_window(
+                &credential.validity,
+                verification_month,
+            ));
+        }
+        let issuer_did = did::Method::new_idp(network, issuer).to_string();
+        if !input.trusted_issuers.contains(&issuer_did)
+            && !input
+                .trusted_identity_providers
+                .iter()
+                .any(|ip| ip.network == network && ip.identity_provider == issuer)
+        {
+            reasons.push(format!("The issuer {} is not trusted", issuer_did));
+        }
+        match expected_claims {
+            Some(expected_claims) => reasons.extend(check_claims_subject(expected_claims, &claims)),
+            None => reasons.push("The credential was not requested".into()),
+        }
+
+        let statements: Vec<StatementVerdict> = expected_statements
+            .iter()
+            .enumerate()
+            .map(|(statement_index, expected)| {
+                let reason = if proof_error.is_some() {
+                    Some("The proofs of the presentation are invalid".into())
+                } else if proven_statements.get(statement_index) != Some(expected) {
+                    Some("The statement was not proven".into())
+                } else {
+                    None
+                };
+                StatementVerdict {
+                    is_valid: reason.is_none(),
+                    reason,
+                }
+            })
+            .collect();
+        if proof_error.is_none() && proven_statements.len() != statements.len() {
+            reasons.push(format!(
+                "Proved {} statements, but {} were requested",
+                proven_statements.len(),
+                statements.len()
+            ));
+        }
+
+        credentials.push(CredentialVerdict {
+            is_valid: reasons.is_empty() && statements.iter().all(|s| s.is_valid),
+            issuer: Some(issuer_did),
+            status,
+            reasons,
+            statements,
+        });
+    }
+
+    let reason = if let Some(error) = proof_error {
+        Some(error)
+    } else if !context_matches {
+        Some("The context of the presentation does not match the request".into())
+    } else if requested.len() != presented.len() {
+        Some(format!(
+            "The presentation has {} credentials, but {} were requested",
+            presented.len(),
+            requested.len()
+        ))
+    } else {
+        None
+    };
+    let verdict = PresentationVerdict {
+        is_valid: reason.is_none() && credentials.iter().all(|c| c.is_valid),
+        reason,
+        credentials,
+    };
+    Ok(serde_json::to_string(&verdict)?)
+}
+
+/// Check that a presentation answers a verification request, i.e. that its
+/// context has the given and requested properties of the request, and that
+/// each credential proves the requested statements with a requested type of
+/// credential from one of the requested issuers. The proofs of the
+/// presentation are not verified.
+pub fn check_presentation_answers_request(
+    request: &VerificationRequest,
+    presentation: &VerifiablePresentationV1,
+) -> Result<()> {
+    let context = &presentation.presentation_context;
+    let given = context
+        .given
+        .iter()
+        .map(LabeledContextProperty::try_from_context_property)
+        .collect::<Result<Vec<_>, _>>()
+        .context("Invalid given context of the presentation")?;
+    ensure!(
+        given == request.context.given,
+        "The given context of the presentation does not match the request"
+    );
+    let requested_labels = context
+        .requested
+        .iter()
+        .map(|property| {
+            LabeledContextProperty::try_from_context_property(property).map(|p| p.label())
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .context("Invalid requested context of the presentation")?;
+    ensure!(
+        requested_labels == request.context.requested,
+        "The requested context of the presentation does not match the request"
+    );
+
+    let presented = &presentation.verifiable_credentials;
+    ensure!(
+        presented.len() == request.subject_claims.len(),
+        "The presentation has {} credentials, but {} were requested",
+        presented.len(),
+        request.subject_claims.len()
+    );
+    for (index, (credential, requested)) in
+        presented.iter().zip(&request.subject_claims).enumerate()
+    {
+        let RequestedSubjectClaims::Identity(requested) = requested;
+        let claims = credential.claims();
+        let source = match claims {
+            SubjectClaims::Account(_) => IdentityCredentialType::AccountCredential,
+            SubjectClaims::Identity(_) => IdentityCredentialType::IdentityCredential,
+        };
+        ensure!(
+            requested.source.contains(&source),
+            "Credential {} is of a type which was not requested",
+            index
+        );
+        let (network, issuer, statements) = claims_parts(&claims);
+        ensure!(
+            requested
+                .issuers
+                .iter()
+                .any(|did| did.network == network && did.identity_provider == issuer),
+            "Credential {} is issued by {}, which was not requested",
+            index,
+            did::Method::new_idp(network, issuer)
+        );
+        let statements: Vec<RequestedStatement<AttributeTag>> = statements
+            .iter()
+            .map(|statement| match statement {
+                AtomicStatementV1::AttributeValue(s) => {
+                    RequestedStatement::RevealAttribute(RevealAttributeStatement {
+                        attribute_tag: s.attribute_tag,
+                    })
+                }
+                AtomicStatementV1::AttributeInRange(s) => {
+                    RequestedStatement::AttributeInRange(s.clone())
+                }
+                AtomicStatementV1::AttributeInSet(s) => {
+                    RequestedStatement::AttributeInSet(s.clone())
+                }
+                AtomicStatementV1::AttributeNotInSet(s) => {
+                    RequestedStatement::AttributeNotInSet(s.clone())
+                }
+            })
+            .collect();
+        ensure!(
+            statements == requested.statements,
+            "Credential {} does not prove the requested statements",
+            index
+        );
+    }
+    Ok(())
+}
+
+/// Convert a JSON value to a CBOR value. Strings, numbers, booleans, null,
+/// arrays and maps are mapped to the corresponding CBOR items, while byte
+/// strings are given as `{"$bytes": "<hex>"}` and already encoded CBOR as
+/// `{"$cbor": "<hex>"}`. Integral numbers are mapped to integers, such that
+/// `1.0` and `1` are the same, as they are in JavaScript. The encoding of the
+/// result with `cbor_encode` is deterministic, with map keys sorted and floats
+/// in their shortest form.
+pub fn json_to_cbor_value(value: &SerdeValue) -> Result<cbor::value::Value> {
+    use cbor::value::Value;
+    let value = match value {
+        SerdeValue::Null => Value::Null,
+        SerdeValue::Bool(b) => Value::Bool(*b),
+        SerdeValue::Number(n) => {
+            if let Some(n) = n.as_u64() {
+                Value::Positive(n)
+            } else if let Some(n) = n.as_i64() {
+                Value::Negative(!(n as u64))
+            } else {
+                let n = n.as_f64().context("Unsupported number")?;
+                if n.fract() == 0.0 && (0.0..2f64.powi(64)).contains(&n) {
+                    Value::Positive(n as u64)
+                } else if n.fract() == 0.0 && (-(2f64.powi(63))..0.0).contains(&n) {
+                    Value::Negative(!(n as i64 as u64))
+                } else {
+                    Value::Float(n)
+                }
+            }
+        }
+        SerdeValue::String(s) => Value::Text(s.clone()),
+        SerdeValue::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(json_to_cbor_value)
+                .collect::<Result<_>>()?,
+        ),
+        SerdeValue::Object(map) => match (map.len(), map.iter().next()) {
+            (1, Some((tag, SerdeValue::String(s)))) if tag == "$bytes" => Value::Bytes(
+                cbor::Bytes(hex::decode(s).context("Expected \"$bytes\" to be hex encoded")?),
+            ),
+            (1, Some((tag, SerdeValue::String(s)))) if tag == "$cbor" => {
+                let bytes = hex::decode(s).context("Expected \"$cbor\" to be hex encoded")?;
+                cbor::cbor_decode(&bytes).context("Expected \"$cbor\" to be a CBOR value")?
+            }
+            _ => Value::Map(
+                map.iter()
+                    .map(|(key, value)| Ok((Value::Text(key.clone()), json_to_cbor_value(value)?)))
+                    .collect::<Result<_>>()?,
+            ),
+        },
+    };
+    Ok(value)
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawAttributeList {
+    valid_to: String,
+    created_at: String,
+    max_accounts: u8,
+    chosen_attributes: BTreeMap<String, String>,
+}
+
+#[derive(SerdeDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodeIdentityAttributesInput {
+    attribute_list: RawAttributeList,
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodedAttribute {
+    raw: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<SerdeValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl From<(String, Result<SerdeValue>)> for DecodedAttribute {
+    fn from((raw, value): (String, Result<SerdeValue>)) -> Self {
+        let (value, error) = match value {
+            Ok(value) => (Some(value), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        DecodedAttribute { raw, value, error }
+    }
+}
+
+#[derive(SerdeSerialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodedAttributeList {
+    valid_to: DecodedAttribute,
+    created_at: DecodedAttribute,
+    max_accounts: u8,
+    chosen_attributes: BTreeMap<String, DecodedAttribute>,
+    /// Whether any of the attributes fail the expected format.
+    has_errors: bool,
+}
+
+/// Decode a date `YYYYMMDD` into an ISO 8601 date `YYYY-MM-DD`.
+fn decode_date(raw: &str) -> Result<SerdeValue> {
+    ensure!(raw.len() == 8, "Expected a date YYYYMMDD");
+    let date =
+        chrono::NaiveDate::parse_from_str(raw, "%Y%m%d").context("Expected a date YYYYMMDD")?;
+    Ok(json!(date.format("%Y-%m-%d").to_string()))
+}
+
+/// Decode a month `YYYYMM` into an ISO 8601 month `YYYY-MM`.
+fn decode_year_month(raw: &str) -> Result<SerdeValue> {
+    ensure!(raw.len() == 6, "Expected a month YYYYMM");
+    decode_date(&format!("{}01", raw)).context("Expected a month YYYYMM")?;
+    Ok(json!(format!("{}-{}", &raw[..4], &raw[4..])))
+}
+
+/// Decode an ISO 3166-1 alpha-2 country code into the code and the name of
+/// the country.
+fn decode_country(raw: &str) -> Result<SerdeValue> {
+    let country = rust_iso3166::from_alpha2(raw)
+        .filter(|_| raw.bytes().all(|b| b.is_ascii_uppercase()))
+        .context("Expected an ISO 3166-1 alpha-2 country code in upper case")?;
+    Ok(json!({ "code": raw, "name": country.name }))
+}
+
+/// Decode the issuer of an ID document, which is either an ISO 3166-1 alpha-2
+/// country code or an ISO 3166-2 subdivision code.
+fn decode_id_doc_issuer(raw: &str) -> Result<SerdeValue> {
+    if raw.len() == 2 {
+        return decode_country(raw);
+    }
+    let subdivision = rust_iso3166::iso3166_2::from_code(raw)
+        .context("Expected an ISO 3166-1 alpha-2 or ISO 3166-2 code")?;
+    Ok(json!({ "code": raw, "name": subdivision.name }))
+}
+
+/// Decode a value which is one of the codes of an enumeration.
+fn decode_enum(raw: &str, names: &[(&str, &str)], expected: &str) -> Result<SerdeValue> {
+    names
+        .iter()
+        .find(|(code, _)| *code == raw)
+        .map(|(_, name)| json!(name))
+        .with_context(|| format!("Expected {}", expected))
+}
+
+fn decode_attribute(tag: &str, raw: &str) -> Result<SerdeValue> {
+    match tag {
+        "dob" | "idDocIssuedAt" | "idDocExpiresAt" => decode_date(raw),
+        "countryOfResidence" | "nationality" | "legalCountry" => decode_country(raw),
+        "idDocIssuer" => decode_id_doc_issuer(raw),
+        "sex" => decode_enum(
+            raw,
+            &[
+                ("0", "NotKnown"),
+                ("1", "Male"),
+                ("2", "Female"),
+                ("9", "NA"),
+            ],
+            "an ISO/IEC 5218 sex code",
+        ),
+        "idDocType" => decode_enum(
+            raw,
+            &[
+                ("0", "NA"),
+                ("1", "Passport"),
+                ("2", "NationalIdCard"),
+                ("3", "DriversLicense"),
+                ("4", "ImmigrationCard"),
+            ],
+            "an ID document type 0-4",
+        ),
+        _ if ATTRIBUTE_NAMES.contains(&tag) => Ok(json!(raw)),
+        _ => bail!("Unknown attribute tag {}", tag),
+    }
+}
+
+/// Decode the attributes of an identity object into typed values. Dates are
+/// given as ISO 8601 strings, countries with their names and enumerated values
+/// by the name of the variant. Values which fail the expected format are
+/// flagged with an error instead of a value.
+pub fn decode_identity_attributes_aux(input: DecodeIdentityAttributesInput) -> Result<JsonString> {
+    let list = input.attribute_list;
+    let chosen_attributes: BTreeMap<String, DecodedAttribute> = list
+        .chosen_attributes
+        .into_iter()
+        .map(|(tag, raw)| {
+            let value = decode_attribute(&tag, &raw);
+            (tag, (raw, value).into())
+        })
+        .collect();
+    let valid_to: DecodedAttribute =
+        (list.valid_to.clone(), decode_year_month(&list.valid_to)).into();
+    let created_at: DecodedAttribute =
+        (list.created_at.clone(), decode_year_month(&list.created_at)).into();
+    let has_errors = valid_to.error.is_some()
+        || created_at.error.is_some()
+        || chosen_attributes.values().any(|a| a.error.is_some());
+    let out = DecodedAttributeList {
+        valid_to,
+        created_at,
+        max_accounts: list.max_accounts,
+        chosen_attributes,
+        has_errors,
+    };
+    Ok(serde_json::to_string(&out)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // The encrypted amounts are created with the deprecated functions that
+    // were used for encrypted transfers before protocol version 7.
+    #[allow(deprecated)]
+    fn decrypt_amounts_and_transfer_to_public() {
+        let mut csprng = thread_rng();
+        let global_context = GlobalContext::<ArCurve>::generate(String::from("genesis_string"));
+        let secret_key =
+            elgamal::SecretKey::generate(global_context.elgamal_generator(), &mut csprng);
+        let public_key = elgamal::PublicKey::from(&secret_key);
+        let (self_amount, _) = encrypted_transfers::encrypt_amount(
+            &global_context,
+            &public_key,
+            Amount::from_micro_ccd(100_000),
+            &mut csprng,
+        );
+        let (incoming_amount, _) = encrypted_transfers::encrypt_amount(
+            &global_context,
+            &public_key,
+            Amount::from_micro_ccd(25_000),
+            &mut csprng,
+        );
+
+        let table =
+            create_decryption_table_aux(&serde_json::to_string(&global_context).unwrap(), None)
+                .unwrap();
+        let encrypted_amounts = vec![
+            base16_encode_string(&self_amount),
+            base16_encode_string(&incoming_amount),
+        ];
+        let amounts = decrypt_amounts_aux(
+            &table,
+            &base16_encode_string(&secret_key),
+            &serde_json::to_string(&encrypted_amounts).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(amounts, r#"["100000","25000"]"#);
+
+        let input = TransferToPublicInput {
+            global_context: global_context.clone(),
+            secret_key: base16_encode_string(&secret_key),
+            self_amount: base16_encode_string(&self_amount),
+            start_index: 0,
+            incoming_amounts: vec![base16_encode_string(&incoming_amount)],
+            amount: Amount::from_micro_ccd(40_000),
+        };
+        let output: SerdeValue =
+            from_str(&create_transfer_to_public_aux(&table, input).unwrap()).unwrap();
+        assert_eq!(output["remainingAmount"], "85000");
+
+        let serialized_payload =
+            hex::decode(output["serializedPayload"].as_str().unwrap()).unwrap();
+        let Payload::TransferToPublic { data } =
+            from_bytes(&mut serialized_payload.as_slice()).unwrap()
+        else {
+            panic!("Expected a transfer to public payload");
+        };
+        let before_amount = encrypted_transfers::aggregate(&self_amount, &incoming_amount);
+        assert!(encrypted_transfers::verify_sec_to_pub_transfer_data(
+            &global_context,
+            &public_key,
+            &before_amount,
+            &data,
+        ));
+    }
+
+    /// Test vectors from the Trezor reference implementation of BIP-39, which
+    /// use the passphrase `TREZOR`.
+    const TREZOR_VECTORS: [(&str, &str, &str); 5] = [
+        (
+            "00000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        ),
+        (
+            "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+        ),
+        (
+            "80808080808080808080808080808080",
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+            "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+            "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+            "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8",
+        ),
+    ];
+
+    /// An identity object issued by a test identity provider with three
+    /// anonymity revokers and threshold two, together with the secrets of
+    /// its holder.
+    struct TestIdentity {
+        ip_info: IpInfo<constants::IpPairing>,
+        ars_infos: BTreeMap<ArIdentity, ArInfo<ArCurve>>,
+        global_context: GlobalContext<ArCurve>,
+        id_use_data: IdObjectUseData<constants::IpPairing, ArCurve>,
+        id_object: IdentityObjectV1<constants::IpPairing, ArCurve, AttributeKind>,
+    }
+
+    fn test_identity() -> TestIdentity {
+        test_identity_with(concordium_base::id::test::test_create_id_use_data(
+            &mut thread_rng(),
+        ))
+    }
+
+    /// An identity object with the given index of a wallet, issued by the
+    /// test identity provider with index 0.
+    fn wallet_test_identity(wallet: &Wallet, identity_index: u32) -> TestIdentity {
+        test_identity_with(id_object_use_data(wallet, 0, identity_index).unwrap())
+    }
+
+    fn test_identity_with(
+        id_use_data: IdObjectUseData<constants::IpPairing, ArCurve>,
+    ) -> TestIdentity {
+        use concordium_base::id::{identity_provider::sign_identity_object_v1, test::*};
+
+        let mut rng = thread_rng();
+        let num_ars = 3;
+        let ip_data = test_create_ip_info(&mut rng, num_ars, 10);
+        let global_context = GlobalContext::generate("genesis".into());
+        let (ars_infos, _) =
+            test_create_ars(&global_context.on_chain_commitment_key.g, num_ars, &mut rng);
+        let (_, pio, _) = test_create_pio_v1(
+            &id_use_data,
+            &ip_data.public_ip_info,
+            &ars_infos,
+            &global_context,
+            num_ars,
+            &mut rng,
+        );
+        let alist = test_create_attributes();
+        let signature = sign_identity_object_v1(
+            &pio,
+            &ip_data.public_ip_info,
+            &alist,
+            &ip_data.ip_secret_key,
+        )
+        .unwrap();
+        TestIdentity {
+            ip_info: ip_data.public_ip_info,
+            ars_infos,
+            global_context,
+            id_use_data,
+            id_object: IdentityObjectV1 {
+                pre_identity_object: pio,
+                alist,
+                signature,
+            },
+        }
+    }
+
+    #[test]
+    fn credential_keys_and_threshold() {
+        let wallet = wallet_from_hex(TEST_SEED, "Testnet").unwrap();
+        let identity = wallet_test_identity(&wallet, 0);
+        let input = |keys: SerdeValue| {
+            let mut input = json!({
+                "ipInfo": identity.ip_info,
+                "globalContext": identity.global_context,
+                "arsInfos": identity.ars_infos,
+                "idObject": identity.id_object,
+                "revealedAttributes": [],
+                "identityIndex": 0,
+                "credNumber": 1,
+                "expiry": u64::MAX,
+            });
+            input
+                .as_object_mut()
+                .unwrap()
+                .extend(keys.as_object().unwrap().clone());
+            from_value(input).unwrap()
+        };
+        let seed_key = |key_index| {
+            hex::encode(
+                wallet
+                    .get_credential_signing_key(0, 0, 1, key_index)
+                    .unwrap()
+                    .verifying_key()
+                    .as_bytes(),
+            )
+        };
+
+        let response: SerdeValue = from_str(
+            &create_credential_v1_aux(
+                &wallet,
+                input(json!({ "keyCount": 3, "threshold": 2, "returnKeys": true })),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let public_keys = &response["credential"]["credentialPublicKeys"];
+        assert_eq!(public_keys["threshold"], json!(2));
+        assert_eq!(response["keys"]["threshold"], json!(2));
+        for key_index in 0..3u8 {
+            let index = key_index.to_string();
+            assert_eq!(
+                public_keys["keys"][&index]["verifyKey"],
+                json!(seed_key(key_index))
+            );
+            assert_eq!(
+                response["keys"]["keys"][&index]["verifyKey"],
+                json!(seed_key(key_index))
+            );
+            assert!(response["keys"]["keys"][&index]["signKey"].is_string());
+        }
+        assert_eq!(public_keys["keys"].as_object().unwrap().len(), 3);
+
+        // Without `returnKeys` only the credential is returned, and the
+        // threshold defaults to 1.
+        let response: SerdeValue =
+            from_str(&create_credential_v1_aux(&wallet, input(json!({ "keyCount": 2 }))).unwrap())
+                .unwrap();
+        assert!(response.get("keys").is_none());
+        assert_eq!(response["credentialPublicKeys"]["threshold"], json!(1));
+        assert_eq!(
+            response["credentialPublicKeys"]["keys"]
+                .as_object()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        for invalid in [
+            json!({ "keyCount": 3, "threshold": 4 }),
+            json!({ "threshold": 2 }),
+            json!({ "keyCount": 0 }),
+            json!({ "keyCount": 1, "keys": [{ "type": "seed" }] }),
+        ] {
+            assert!(create_credential_v1_aux(&wallet, input(invalid)).is_err());
+        }
+    }
+
+    #[test]
+    fn derive_credentials_matches_single_derivation() {
+        let wallet = wallet_from_hex(TEST_SEED, "Testnet").unwrap();
+        let global_context = GlobalContext::<ArCurve>::generate("genesis".into());
+        let commitment_key = base16_encode_string(&global_context.on_chain_commitment_key);
+        let derive = |ips: SerdeValue, identities: SerdeValue, counters: SerdeValue| {
+            let input = from_value(json!({
+                "globalContext": global_context,
+                "identityProviderIndices": ips,
+                "identityIndices": identities,
+                "credentialCounters": counters,
+            }))
+            .unwrap();
+            derive_credentials_aux(&wallet, input)
+        };
+
+        let derived: Vec<SerdeValue> = from_str(
+            &derive(
+                json!([0, 3]),
+                json!({ "start": 1, "count": 2 }),
+                json!({ "start": 254, "count": 2 }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let mut expected = Vec::new();
+        for ip in [0, 3] {
+            for identity in 1..3 {
+                for counter in 254..=u8::MAX {
+                    let cred_id =
+                        credential_id_aux(&wallet, ip, identity, counter, &commitment_key).unwrap();
+                    let cred_id_point: ArCurve = base16_decode_string(&cred_id).unwrap();
+                    expected.push(json!({
+                        "identityProviderIndex": ip,
+                        "identityIndex": identity,
+                        "credentialCounter": counter,
+                        "credId": cred_id,
+                        "publicKey": account_public_key_aux(&wallet, ip, identity, counter.into())
+                            .unwrap(),
+                        "accountAddress": account_address_from_registration_id(&cred_id_point),
+                    }));
+                }
+            }
+        }
+        assert_eq!(derived, expected);
+
+        let empty = json!({ "start": 0, "count": 0 });
+        let one = json!({ "start": 0, "count": 1 });
+        assert_eq!(derive(json!([]), one.clone(), one.clone()).unwrap(), "[]");
+        assert_eq!(
+            derive(json!([0]), empty.clone(), one.clone()).unwrap(),
+            "[]"
+        );
+        assert_eq!(derive(json!([0]), one.clone(), empty).unwrap(), "[]");
+
+        // Credential counters are at most 255, and ranges must not overflow.
+        assert!(derive(json!([0]), one.clone(), json!({ "start": 255, "count": 2 })).is_err());
+        assert!(derive(
+            json!([0]),
+            one.clone(),
+            json!({ "start": 200, "count": 100 })
+        )
+        .is_err());
+        assert!(derive(json!([0]), json!({ "start": u32::MAX, "count": 2 }), one).is_err());
+    }
+
+    #[test]
+    fn decode_identity_attributes() {
+        let ok = |tag: &'static str, raw: &'static str, value: SerdeValue| (tag, raw, Ok(value));
+        let err =
+            |tag: &'static str, raw: &'static str, error: &str| (tag, raw, Err(error.to_string()));
+        let country = "Expected an ISO 3166-1 alpha-2 country code in upper case";
+        let cases = [
+            ok("dob", "19950228", json!("1995-02-28")),
+            err("dob", "19950229", "Expected a date YYYYMMDD"),
+            err("dob", "1995-02-28", "Expected a date YYYYMMDD"),
+            ok("idDocIssuedAt", "20200101", json!("2020-01-01")),
+            err("idDocIssuedAt", "20201301", "Expected a date YYYYMMDD"),
+            ok("idDocExpiresAt", "20301231", json!("2030-12-31")),
+            err("idDocExpiresAt", "2030123", "Expected a date YYYYMMDD"),
+            ok(
+                "countryOfResidence",
+                "DK",
+                json!({ "code": "DK", "name": "Denmark" }),
+            ),
+            err("countryOfResidence", "dk", country),
+            ok(
+                "nationality",
+                "DE",
+                json!({ "code": "DE", "name": "Germany" }),
+            ),
+            err("nationality", "XX", country),
+            ok(
+                "legalCountry",
+                "NO",
+                json!({ "code": "NO", "name": "Norway" }),
+            ),
+            err("legalCountry", "NOR", country),
+            ok(
+                "idDocIssuer",
+                "SE",
+                json!({ "code": "SE", "name": "Sweden" }),
+            ),
+            ok(
+                "idDocIssuer",
+                "DK-84",
+                json!({ "code": "DK-84", "name": "Hovedstaden" }),
+            ),
+            err(
+                "idDocIssuer",
+                "DK-99",
+                "Expected an ISO 3166-1 alpha-2 or ISO 3166-2 code",
+            ),
+            err("idDocIssuer", "ZZ", country),
+            ok("sex", "2", json!("Female")),
+            err("sex", "3", "Expected an ISO/IEC 5218 sex code"),
+            ok("idDocType", "1", json!("Passport")),
+            err("idDocType", "5", "Expected an ID document type 0-4"),
+            ok("firstName", "Anna", json!("Anna")),
+            err("unknownTag", "x", "Unknown attribute tag unknownTag"),
+        ];
+        for (tag, raw, expected) in cases {
+            let input = from_value(json!({
+                "attributeList": {
+                    "validTo": "203012",
+                    "createdAt": "202001",
+                    "maxAccounts": 200,
+                    "chosenAttributes": { tag: raw },
+                },
+            }))
+            .unwrap();
+            let decoded: SerdeValue =
+                from_str(&decode_identity_attributes_aux(input).unwrap()).unwrap();
+            let attribute = match expected {
+                Ok(value) => json!({ "raw": raw, "value": value }),
+                Err(error) => json!({ "raw": raw, "error": error }),
+            };
+            assert_eq!(
+                decoded,
+                json!({
+                    "validTo": { "raw": "203012", "value": "2030-12" },
+                    "createdAt": { "raw": "202001", "value": "2020-01" },
+                    "maxAccounts": 200,
+                    "chosenAttributes": { tag: attribute },
+                    "hasErrors": attribute.get("error").is_some(),
+                }),
+                "{} {}",
+                tag,
+                raw
+            );
+        }
+
+        let input = from_value(json!({
+            "attributeList": {
+                "validTo": "203013",
+                "createdAt": "2020",
+                "maxAccounts": 200,
+                "chosenAttributes": {},
+            },
+        }))
+        .unwrap();
+        let decoded: SerdeValue =
+            from_str(&decode_identity_attributes_aux(input).unwrap()).unwrap();
+        assert_eq!(
+            decoded["validTo"],
+            json!({ "raw": "203013", "error": "Expected a month YYYYMM" })
+        );
+        assert_eq!(
+            decoded["createdAt"],
+            json!({ "raw": "2020", "error": "Expected a month YYYYMM" })
+        );
+        assert_eq!(decoded["hasErrors"], json!(true));
+    }
+
+    #[test]
+    fn verify_id_proof() {
+        let wallet = wallet_from_hex(TEST_SEED, "Testnet").unwrap();
+        let identity = wallet_test_identity(&wallet, 0);
+        let credential_input = from_value(json!({
+            "ipInfo": identity.ip_info,
+            "globalContext": identity.global_context,
+            "arsInfos": identity.ars_infos,
+            "idObject": identity.id_object,
+            "revealedAttributes": [],
+            "identityIndex": 0,
+            "credNumber": 1,
+            "expiry": u64::MAX,
+        }))
+        .unwrap();
+        let cdi: CredentialDeploymentInfo<constants::IpPairing, ArCurve, AttributeKind> =
+            from_str(&create_credential_v1_aux(&wallet, credential_input).unwrap()).unwrap();
+
+        // The test identity has the first name 55 and the id document issuer 31.
+        let statement = json!([
+            { "type": "RevealAttribute", "attributeTag": "firstName" },
+            { "type": "AttributeInSet", "attributeTag": "idDocIssuer", "set": ["31", "DK"] },
+        ]);
+        let challenge = hex::encode([7u8; 32]);
+        let proof_input = from_value(json!({
+            "idObject": identity.id_object,
+            "globalContext": identity.global_context,
+            "identityProviderIndex": 0,
+            "identityIndex": 0,
+            "credNumber": 1,
+            "statement": statement,
+            "challenge": challenge,
+        }))
+        .unwrap();
+        let output: SerdeValue =
+            from_str(&create_id_proof_aux(&wallet, proof_input).unwrap()).unwrap();
+        assert_eq!(
+            output["credential"],
+            json!(base16_encode_string(&cdi.values.cred_id))
+        );
+
+        let verify = |statement: &SerdeValue, challenge: &str| {
+            let input = from_value(json!({
+                "statement": statement,
+                "proof": output["proof"],
+                "challenge": challenge,
+                "globalContext": identity.global_context,
+                "credential": output["credential"],
+                "commitments": cdi.proofs.id_proofs.commitments,
+            }))
+            .unwrap();
+            verify_id_proof_aux(input).unwrap()
+        };
+        assert!(verify(&statement, &challenge));
+        assert!(!verify(&statement, &hex::encode([8u8; 32])));
+
+        let mut other_set = statement.clone();
+        other_set[1]["set"] = json!(["32", "DK"]);
+        assert!(!verify(&other_set, &challenge));
+        let mut other_attribute = statement.clone();
+        other_attribute[0]["attributeTag"] = json!("lastName");
+        assert!(!verify(&other_attribute, &challenge));
+    }
+
+    #[test]
+    fn verify_credential_deployment() {
+        let wallet = wallet_from_hex(TEST_SEED, "Testnet").unwrap();
+        let identity = wallet_test_identity(&wallet, 0);
+        let expiry = TransactionTime::from_seconds(u64::MAX);
+        let credential = |cred_number: u8| {
+            let input = from_value(json!({
+                "ipInfo": identity.ip_info,
+                "globalContext": identity.global_context,
+                "arsInfos": identity.ars_infos,
+                "idObject": identity.id_object,
+                "revealedAttributes": [],
+                "identityIndex": 0,
+                "credNumber": cred_number,
+                "expiry": expiry,
+            }))
+            .unwrap();
+            from_str::<CredentialDeploymentInfo<constants::IpPairing, ArCurve, AttributeKind>>(
+                &create_credential_v1_aux(&wallet, input).unwrap(),
+            )
+            .unwrap()
+        };
+        let verify = |credential: &CredentialDeploymentInfo<_, _, _>, expiry: TransactionTime| {
+            let input = from_value(json!({
+                "ipInfo": identity.ip_info,
+                "arsInfos": identity.ars_infos,
+                "globalContext": identity.global_context,
+                "credential": credential,
+                "expiry": expiry,
+            }))
+            .unwrap();
+            from_str::<SerdeValue>(&verify_credential_deployment_aux(input).unwrap()).unwrap()
+        };
+
+        let valid = credential(1);
+        assert_eq!(verify(&valid, expiry), json!({ "isValid": true }));
+
+        let mut tampered = valid.clone();
+        tampered.proofs.id_proofs.proof_reg_id = credential(2).proofs.id_proofs.proof_reg_id;
+        assert_eq!(
+            verify(&tampered, expiry),
+            json!({ "isValid": false, "reason": "The proofs of the credential are invalid" })
+        );
+
+        // The keys of the credential sign the expiry of the transaction.
+        assert_eq!(
+            verify(&valid, TransactionTime::from_seconds(u64::MAX - 1)),
+            json!({
+                "isValid": false,
+                "reason": "The signatures of the keys of the credential are invalid",
+            })
+        );
+    }
+
+    #[test]
+    fn existing_account_credential_is_bound_to_the_account() {
+        let wallet = wallet_from_hex(TEST_SEED, "Testnet").unwrap();
+        let identity = wallet_test_identity(&wallet, 2);
+        let address: AccountAddress = SIGN_MESSAGE_ACCOUNT_SINGLE.parse().unwrap();
+        let input = json!({
+            "ipInfo": identity.ip_info,
+            "globalContext": identity.global_context,
+            "arsInfos": identity.ars_infos,
+            "idObject": identity.id_object,
+            "revealedAttributes": [],
+            "identityIndex": 2,
+            "credNumber": 1,
+            "address": address,
+            "credentialIndex": 1,
+            "newThreshold": 2,
+        });
+        let response: SerdeValue = from_str(
+            &create_existing_account_credential_v1_aux(&wallet, from_value(input.clone()).unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(response.get("keys").is_none());
+        let cdi: CredentialDeploymentInfo<constants::IpPairing, ArCurve, AttributeKind> =
+            from_value(response["credential"].clone()).unwrap();
+        let verify = |new_or_existing| {
+            verify_cdi(
+                &identity.global_context,
+                &identity.ip_info,
+                &identity.ars_infos,
+                &cdi,
+                &new_or_existing,
+            )
+        };
+        assert!(verify(Right(address)).is_ok());
+        assert!(verify(Right(SIGN_MESSAGE_ACCOUNT_MULTI.parse().unwrap())).is_err());
+        assert!(verify(Left(TransactionTime::from_seconds(u64::MAX))).is_err());
+
+        let payload = Payload::UpdateCredentials {
+            new_cred_infos: [(CredentialIndex { index: 1 }, cdi)].into(),
+            remove_cred_ids: Vec::new(),
+            new_threshold: AccountThreshold::try_from(2).unwrap(),
+        };
+        assert_eq!(
+            response["serializedPayload"],
+            json!(hex::encode(to_bytes(&payload)))
+        );
+
+        let mut without_address = input.clone();
+        without_address["address"] = json!(null);
+        without_address["expiry"] = json!(u64::MAX);
+        assert!(create_existing_account_credential_v1_aux(
+            &wallet,
+            from_value(without_address).unwrap()
+        )
+        .is_err());
+        let mut zero_threshold = input;
+        zero_threshold["newThreshold"] = json!(0);
+        assert!(from_value::<ExistingAccountCredentialInput>(zero_threshold).is_err());
+    }
+
+    #[test]
+    fn verify_identity_object() {
+        let identity = test_identity();
+        let aci = &identity.id_use_data.aci;
+        let id_object = serde_json::to_value(&identity.id_object).unwrap();
+        let secrets = json!({
+            "idCredSec": base16_encode_string(&aci.cred_holder_info.id_cred.id_cred_sec),
+            "prfKey": base16_encode_string(&aci.prf_key),
+            "blindingRandomness": base16_encode_string(&identity.id_use_data.randomness),
+        });
+        let verify_with = |id_object: &SerdeValue,
+                           ars_infos: &BTreeMap<ArIdentity, ArInfo<ArCurve>>,
+                           verification_time: &str,
+                           secrets: &SerdeValue| {
+            let mut input = json!({
+                "ipInfo": identity.ip_info,
+                "arsInfos": ars_infos,
+                "globalContext": identity.global_context,
+                "idObject": id_object,
+                "verificationTime": verification_time,
+            });
+            input
+                .as_object_mut()
+                .unwrap()
+                .extend(secrets.as_object().unwrap().clone());
+            let input = from_value(input).unwrap();
+            from_str::<SerdeValue>(&verify_identity_object_aux(input).unwrap()).unwrap()
+        };
+        let verify = |id_object: &SerdeValue,
+                      ars_infos: &BTreeMap<ArIdentity, ArInfo<ArCurve>>,
+                      verification_time: &str| {
+            verify_with(id_object, ars_infos, verification_time, &secrets)
+        };
+
+        // Without the secrets, everything but the signature is checked from the
+        // public inputs.
+        let public_report = |id_object: &SerdeValue| {
+            verify_with(
+                id_object,
+                &identity.ars_infos,
+                "2021-01-01T00:00:00Z",
+                &json!({}),
+            )
+        };
+        assert_eq!(
+            public_report(&id_object),
+            json!({
+                "isValid": true,
+                "proofsValid": true,
+                "signatureValid": true,
+                "signatureVerified": false,
+                "notExpired": true,
+                "createdAtValid": true,
+                "arDataValid": true,
+                "arDataErrors": [],
+            })
+        );
+        let mut other_id_cred_pub = id_object.clone();
+        other_id_cred_pub["preIdentityObject"]["idCredPub"] =
+            json!(base16_encode_string(&ArCurve::generate(&mut thread_rng())));
+        let report = public_report(&other_id_cred_pub);
+        assert_eq!(report["isValid"], json!(false));
+        assert_eq!(report["proofsValid"], json!(false));
+        let mut lowered = id_object.clone();
+        lowered["preIdentityObject"]["choiceArData"]["threshold"] = json!(1);
+        let report = public_report(&lowered);
+        assert_eq!(report["isValid"], json!(false));
+        assert_eq!(report["proofsValid"], json!(false));
+        let mut other_global_context = identity.global_context.clone();
+        other_global_context.genesis_string = "other".into();
+        let input = from_value(json!({
+            "ipInfo": identity.ip_info,
+            "arsInfos": identity.ars_infos,
+            "globalContext": other_global_context,
+            "idObject": id_object,
+            "verificationTime": "2021-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        let report: SerdeValue = from_str(&verify_identity_object_aux(input).unwrap()).unwrap();
+        assert_eq!(report["proofsValid"], json!(false));
+
+        // The attribute list is valid from May 2020 to May 2022.
+        let report = verify(&id_object, &identity.ars_infos, "2021-01-01T00:00:00Z");
+        assert_eq!(
+            report,
+            json!({
+                "isValid": true,
+                "proofsValid": true,
+                "signatureValid": true,
+                "signatureVerified": true,
+                "notExpired": true,
+                "createdAtValid": true,
+                "arDataValid": true,
+                "arDataErrors": [],
+            })
+        );
+
+        let report = verify(&id_object, &identity.ars_infos, "2022-06-01T00:00:00Z");
+        assert_eq!(report["isValid"], json!(false));
+        assert_eq!(report["signatureValid"], json!(true));
+        assert_eq!(report["notExpired"], json!(false));
+        let report = verify(&id_object, &identity.ars_infos, "2020-04-01T00:00:00Z");
+        assert_eq!(report["isValid"], json!(false));
+        assert_eq!(report["createdAtValid"], json!(false));
+
+        let mut tampered = id_object.clone();
+        tampered["attributeList"]["chosenAttributes"]["firstName"] = json!("56");
+        let report = verify(&tampered, &identity.ars_infos, "2021-01-01T00:00:00Z");
+        assert_eq!(report["isValid"], json!(false));
+        assert_eq!(report["signatureValid"], json!(false));
+        assert_eq!(report["arDataValid"], json!(true));
+
+        // The threshold is signed, so lowering it invalidates the signature.
+        let report = verify(&lowered, &identity.ars_infos, "2021-01-01T00:00:00Z");
+        assert_eq!(report["isValid"], json!(false));
+        assert_eq!(report["signatureValid"], json!(false));
+
+        let mut raised = id_object.clone();
+        raised["preIdentityObject"]["choiceArData"]["threshold"] = json!(4);
+        let report = verify(&raised, &identity.ars_infos, "2021-01-01T00:00:00Z");
+        assert_eq!(report["arDataValid"], json!(false));
+        assert_eq!(
+            report["arDataErrors"],
+            json!(["Invalid threshold 4 for 3 anonymity revokers"])
+        );
+
+        let mut ars_infos = identity.ars_infos.clone();
+        ars_infos.remove(&ArIdentity::try_from(2).unwrap());
+        let report = verify(&id_object, &ars_infos, "2021-01-01T00:00:00Z");
+        assert_eq!(report["isValid"], json!(false));
+        assert_eq!(
+            report["arDataErrors"],
+            json!(["Anonymity revoker 2 is not known"])
+        );
+    }
+
+    #[test]
+    fn mnemonic_trezor_vectors() {
+        for (entropy, mnemonic, seed) in TREZOR_VECTORS {
+            let input = GenerateMnemonicInput {
+                word_count: mnemonic.split_whitespace().count(),
+                entropy: Some(entropy.to_string()),
+            };
+            assert_eq!(generate_mnemonic_aux(input).unwrap(), mnemonic);
+
+            let input = ValidateMnemonicInput {
+                mnemonic: mnemonic.to_string(),
+            };
+            let report: SerdeValue = from_str(&validate_mnemonic_aux(input).unwrap()).unwrap();
+            assert_eq!(report["isValid"], true);
+
+            let input = MnemonicToSeedInput {
+                mnemonic: mnemonic.to_string(),
+                passphrase: "TREZOR".to_string(),
+            };
+            assert_eq!(mnemonic_to_seed_aux(input).unwrap(), seed);
+        }
+    }
+
+    #[test]
+    fn mnemonic_with_wrong_checksum_is_rejected() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        let input = from_value(json!({ "mnemonic": mnemonic })).unwrap();
+        let report: SerdeValue = from_str(&validate_mnemonic_aux(input).unwrap()).unwrap();
+        assert_eq!(report["isValid"], false);
+        assert_eq!(report["invalidWords"], json!([]));
+        assert!(mnemonic_to_seed(mnemonic, "TREZOR").is_err());
+    }
+
+    #[test]
+    fn mnemonic_with_invalid_word_is_rejected() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandonn about";
+        let input = from_value(json!({ "mnemonic": mnemonic })).unwrap();
+        let report: SerdeValue = from_str(&validate_mnemonic_aux(input).unwrap()).unwrap();
+        assert_eq!(report["isValid"], false);
+        assert_eq!(report["invalidWords"], json!([10]));
+        assert!(mnemonic_to_seed(mnemonic, "TREZOR").is_err());
+    }
+
+    /// A mobile wallet export encrypted by the encryption module of
+    /// `concordium_base` with the password `correct horse battery staple`.
+    const MOBILE_WALLET_EXPORT_FIXTURE: &str = r#"{"metadata":{"encryptionMethod":"AES-256","keyDerivationMethod":"PBKDF2WithHmacSHA256","iterations":100000,"salt":"mkXGfT6Wp+UAfBEO3jTexQ==","initializationVector":"eaW5QlbQGOaVZEW1Frkr5Q=="},"cipherText":"Q8k4AHYsPzDGt4TCDLD8B7JcGDNcUx3AwWgiMEB3SltkxM2Wbjl6E12vmdKgWh9H4yl0pVUbiymuJXHZ5SIeTThwo3U3RgcVhfQqZ/oMRQhUyWaCTikNdwfJIFxIyWCSvy4rDJqbtwxPEtx3puO+Ul64dSoWFEijBPABfEyRXjx3C9kCofxvHAsFTioCxpxT+A+1finpEN58eFXCh2QYZGl610efqGEJyNhV35/8GXrv0a81H1mRQyU9iZGH/fZhr+uL2bhJCPJRhA0CWLFTpNy3+ZlnK2l460O0SHgIZUXvNYJbSfx8EAJTq3AJEMNe/q02BHxSQh+yCxVhbo2OZ9VIfueA8z7ThF8guuiTSLyDMF3QN0xRd34zfhFj3HXgXMGa/+yEn+z9Hp8nHVUFHkw+EgI51cIqJHfH+iT2EN8+RJk5DxzQE9BCK5JvzDQ5z706+mjVkAfPofxDqKLTtXmB0rt/VXqxe2nJnPzxoRs5W9N1jPXy9U/B52Mv/aV3ROfzAoCLLfR70H86b60mgLO7O2IIHji9Vn6/OGURmAXbfXSZWFIzFxrwqdpaCFtsMgJ6m/fArIaNyfsrlPsAaigzFLkaCXZrvR1VD1xshuAB3aXj7RBfVHo2e4m8Aase2m7hF6n9DyIIDrvFohpeuXbBnhk7951hn08hDtrp1+JGU/KvQPb+qRgCuBK/G2wb1sTZL1KrVwngHG9LLJHwi5WE1NLZIUg2NvxnEN4Pp4s="}"#;
+
+    #[test]
+    fn decrypt_mobile_wallet_export_fixture() {
+        let input = DecryptWalletExportInput {
+            export: from_str(MOBILE_WALLET_EXPORT_FIXTURE).unwrap(),
+            password: "correct horse battery staple".to_string(),
+        };
+        let export: SerdeValue = from_str(&decrypt_wallet_export_aux(input).unwrap()).unwrap();
+        assert_eq!(export["type"], "concordium-mobile-wallet-data");
+        let account = &export["value"]["identities"][0]["accounts"][0];
+        assert_eq!(account["name"], "Account 1");
+        assert_eq!(
+            account["address"],
+            "2zqgdLU9hpajqS5W1CWkRLoqCoUnLSt3YGNCaig6LGBbv2usqs"
+        );
+        let key = &account["accountKeys"]["keys"]["0"]["keys"]["0"];
+        assert_eq!(key["signKey"], "01".repeat(32));
+        assert_eq!(
+            key["verifyKey"],
+            "8a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c"
+        );
+
+        let input = DecryptWalletExportInput {
+            export: from_str(MOBILE_WALLET_EXPORT_FIXTURE).unwrap(),
+            password: "wrong password".to_string(),
+        };
+        assert!(decrypt_wallet_export_aux(input).is_err());
+    }
+
+    #[test]
+    fn encrypt_wallet_export_round_trip() {
+        let password = "correct horse battery staple".to_string();
+        let plaintext = encryption::decrypt(
+            &Password::from(password.clone()),
+            &from_str(MOBILE_WALLET_EXPORT_FIXTURE).unwrap(),
+        )
+        .unwrap();
+        let input = EncryptWalletExportInput {
+            export: serde_json::from_slice(&plaintext).unwrap(),
+            password: password.clone(),
+        };
+        let encrypted = encrypt_wallet_export_aux(input).unwrap();
+
+        let decrypted =
+            encryption::decrypt(&Password::from(password), &from_str(&encrypted).unwrap()).unwrap();
+        let expected: SerdeValue = serde_json::from_slice(&plaintext).unwrap();
+        let actual: SerdeValue = serde_json::from_slice(&decrypted).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    /// The seed of the BIP-39 mnemonic `abandon ... about` with an empty
+    /// passphrase.
+    const TEST_SEED: &str = "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4";
+
+    /// Check that all keys derived by two wallets are the same.
+    fn assert_same_keys(wallet: &Wallet, other: &Wallet) {
+        let issuer = ContractAddress::new(3, 4);
+        assert_eq!(
+            wallet.get_account_signing_key(1, 2, 3).unwrap().to_bytes(),
+            other.get_account_signing_key(1, 2, 3).unwrap().to_bytes()
+        );
+        assert_eq!(
+            wallet.get_account_public_key(1, 2, 3).unwrap(),
+            other.get_account_public_key(1, 2, 3).unwrap()
+        );
+        assert_eq!(
+            wallet
+                .get_credential_signing_key(1, 2, 3, 1)
+                .unwrap()
+                .to_bytes(),
+            other
+                .get_credential_signing_key(1, 2, 3, 1)
+                .unwrap()
+                .to_bytes()
+        );
+        assert_eq!(
+            wallet.get_id_cred_sec(1, 2).unwrap(),
+            other.get_id_cred_sec(1, 2).unwrap()
+        );
+        assert_eq!(
+            to_bytes(&wallet.get_prf_key(1, 2).unwrap()),
+            to_bytes(&other.get_prf_key(1, 2).unwrap())
+        );
+        assert_eq!(
+            to_bytes(&wallet.get_blinding_randomness(1, 2).unwrap()),
+            to_bytes(&other.get_blinding_randomness(1, 2).unwrap())
+        );
+        assert_eq!(
+            to_bytes(
+                &wallet
+                    .get_attribute_commitment_randomness(1, 2, 3, AttributeTag(4))
+                    .unwrap()
+            ),
+            to_bytes(
+                &other
+                    .get_attribute_commitment_randomness(1, 2, 3, AttributeTag(4))
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            wallet
+                .get_verifiable_credential_signing_key(issuer, 5)
+                .unwrap()
+                .to_bytes(),
+            other
+                .get_verifiable_credential_signing_key(issuer, 5)
+                .unwrap()
+                .to_bytes()
+        );
+        assert_eq!(
+            wallet
+                .get_verifiable_credential_public_key(issuer, 5)
+                .unwrap(),
+            other
+                .get_verifiable_credential_public_key(issuer, 5)
+                .unwrap()
+        );
+        assert_eq!(
+            wallet
+                .get_verifiable_credential_backup_encryption_key()
+                .unwrap(),
+            other
+                .get_verifiable_credential_backup_encryption_key()
+                .unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(wallet.get_baker_keys(1, 2, 3, 4).unwrap()).unwrap(),
+            serde_json::to_value(other.get_baker_keys(1, 2, 3, 4).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn custom_wallet_derives_at_the_paths_of_known_nets() {
+        let seed: [u8; 64] = hex::decode(TEST_SEED).unwrap().try_into().unwrap();
+        for net in [Net::Mainnet, Net::Testnet] {
+            let known = Wallet::Known(ConcordiumHdWallet { seed, net });
+            let custom = Wallet::Custom {
+                seed,
+                coin_type: net.net_code(),
+            };
+            assert_same_keys(&known, &custom);
+        }
+    }
+
+    #[test]
+    fn custom_net_keys_only_depend_on_the_coin_type() {
+        let wallet = wallet_from_hex(TEST_SEED, "Stagenet:7919").unwrap();
+        assert_same_keys(&wallet, &wallet_from_hex(TEST_SEED, "Devnet:7919").unwrap());
+        assert_ne!(
+            wallet.get_account_public_key(1, 2, 3).unwrap(),
+            wallet_from_hex(TEST_SEED, "Stagenet:7920")
+                .unwrap()
+                .get_account_public_key(1, 2, 3)
+                .unwrap()
+        );
+        assert!(wallet_from_hex(TEST_SEED, "Stagenet:919").is_err());
+        assert!(wallet_from_hex(TEST_SEED, ":7919").is_err());
+        assert!(wallet_from_hex(TEST_SEED, "Stagenet").is_err());
+    }
+
+    #[test]
+    fn baker_keys_vector() {
+        let wallet = wallet_from_hex(TEST_SEED, "Testnet").unwrap();
+        let keys = serde_json::to_value(wallet.get_baker_keys(0, 0, 0, 0).unwrap()).unwrap();
+        assert_eq!(
+            keys,
+            json!({
+                "electionPrivateKey": "9518fe9ea26939788da3fdb87f137c8b3c14b1ba641fd75e1bb62e495f3a9d56",
+                "electionVerifyKey": "f315e814acec8c873b994a9ea63ba809bb9cda441079388b5985c2faf215a2c4",
+                "signatureSignKey": "1c747bc931a34abf310febb39cead96847c593c8e5ce9f7705745a7ac979f9ae",
+                "signatureVerifyKey": "bda835d610832e484cdb0af03629298d0aef3a3af09100cb814a7c5e883ee0b9",
+                "aggregationSignKey": "0ea24d8f033a507d5c1c581149d39e5002eda443df69f0d9f6d9ef450a7c716d",
+                "aggregationVerifyKey": "aa7940563a4f2e4bdc6d3fd29cc995a8dee31215a753b6c8f0c41867565db6cc976aea59ba4afa433d0197ec3846c8e31638de0da8034587190dc7f5ba86930ec926672445c14cfe81c82c150948f2fa9039566bb4f9061171cec19e9377a48f",
+            })
+        );
+    }
+
+    #[test]
+    fn configure_baker_keys_proofs() {
+        let mut rng = thread_rng();
+        let keys = BakerKeyPairs::generate(&mut rng);
+        let sender: AccountAddress = SIGN_MESSAGE_ACCOUNT_SINGLE.parse().unwrap();
+        let other: AccountAddress = SIGN_MESSAGE_ACCOUNT_MULTI.parse().unwrap();
+        let payload =
+            serde_json::to_value(ConfigureBakerKeysPayload::new(&keys, sender, &mut rng)).unwrap();
+        let verify = |payload: &SerdeValue, sender: AccountAddress| {
+            let input = from_value(json!({ "payload": payload, "sender": sender })).unwrap();
+            from_str::<SerdeValue>(&verify_configure_baker_keys_aux(input).unwrap()).unwrap()
+        };
+
+        let all_valid = json!({
+            "isValid": true,
+            "electionProofValid": true,
+            "signatureProofValid": true,
+            "aggregationProofValid": true,
+        });
+        let all_invalid = json!({
+            "isValid": false,
+            "electionProofValid": false,
+            "signatureProofValid": false,
+            "aggregationProofValid": false,
+        });
+        assert_eq!(verify(&payload, sender), all_valid);
+        assert_eq!(verify(&payload, other), all_invalid);
+
+        // Replacing one of the keys changes the challenge of every proof.
+        let other_keys = serde_json::to_value(BakerKeyPairs::generate(&mut rng)).unwrap();
+        let mut swapped = payload.clone();
+        swapped["signatureVerifyKey"] = other_keys["signatureVerifyKey"].clone();
+        assert_eq!(verify(&swapped, sender), all_invalid);
+    }
+
+    #[test]
+    fn web3_id_credential_signature_round_trip() {
+        let global_context = GlobalContext::<ArCurve>::generate(String::from("genesis_string"));
+        let issuer_key = SigningKey::from_bytes(&[2u8; 32]);
+        let holder = SigningKey::from_bytes(&[3u8; 32]).verifying_key();
+        let values = json!({ "name": "Alice", "age": 42 });
+        let input = json!({
+            "globalContext": global_context,
+            "values": values,
+            "holder": hex::encode(holder.to_bytes()),
+            "issuerContract": { "index": 5, "subindex": 0 },
+            "issuerKey": hex::encode(issuer_key.to_bytes()),
+        });
+        let signed: SerdeValue =
+            from_str(&sign_web3_id_credential_aux(from_value(input).unwrap()).unwrap()).unwrap();
+
+        let verify = |values: &SerdeValue, signature: &SerdeValue| {
+            let input = json!({
+                "globalContext": global_context,
+                "values": values,
+                "randomness": signed["randomness"],
+                "signature": signature,
+                "holder": hex::encode(holder.to_bytes()),
+                "issuerPublicKey": hex::encode(issuer_key.verifying_key().to_bytes()),
+                "issuerContract": { "index": 5, "subindex": 0 },
+            });
+            verify_web3_id_credential_signature_aux(from_value(input).unwrap()).unwrap()
+        };
+        assert!(verify(&values, &signed["signature"]));
+
+        let mut signature = hex::decode(signed["signature"].as_str().unwrap()).unwrap();
+        signature[0] ^= 1;
+        assert!(!verify(&values, &json!(hex::encode(signature))));
+        assert!(!verify(
+            &json!({ "name": "Alice", "age": 43 }),
+            &signed["signature"]
+        ));
+    }
+
+    #[test]
+    fn web3_id_backup_rejects_modifications_and_other_seeds() {
+        let wallet = wallet_from_hex(TEST_SEED, "Testnet").unwrap();
+        let credentials = json!([{
+            "values": { "name": "Alice" },
+            "randomness": { "name": PedersenRandomness::<ArCurve>::generate(&mut thread_rng()) },
+            "metadata": { "issuer": "Test issuer" },
+        }]);
+        let input = EncryptWeb3IdBackupInput {
+            credentials: from_value(credentials.clone()).unwrap(),
+        };
+        let backup: SerdeValue =
+            from_str(&encrypt_web3_id_backup_aux(&wallet, input).unwrap()).unwrap();
+
+        let decrypt = |wallet: &Wallet, backup: &SerdeValue| {
+            let input = DecryptWeb3IdBackupInput {
+                backup: from_value(backup.clone()).unwrap(),
+            };
+            decrypt_web3_id_backup_aux(wallet, input)
+        };
+        let decrypted: SerdeValue = from_str(&decrypt(&wallet, &backup).unwrap()).unwrap();
+        assert_eq!(decrypted, credentials);
+
+        let flip_byte = |field: &str| {
+            let mut bytes = hex::decode(backup[field].as_str().unwrap()).unwrap();
+            bytes[0] ^= 1;
+            let mut modified = backup.clone();
+            modified[field] = json!(hex::encode(bytes));
+            modified
+        };
+        assert!(decrypt(&wallet, &flip_byte("ciphertext")).is_err());
+        assert!(decrypt(&wallet, &flip_byte("nonce")).is_err());
+        assert!(decrypt(&wallet, &flip_byte("keyId")).is_err());
+
+        let mut other_seed = hex::decode(TEST_SEED).unwrap();
+        other_seed[0] ^= 1;
+        let other_wallet = wallet_from_seed(&other_seed, "Testnet").unwrap();
+        assert!(decrypt(&other_wallet, &backup).is_err());
+    }
+
+    #[test]
+    fn presentation_satisfiability_of_account_candidates() {
+        use concordium_base::{
+            id::id_proof_types::{AttributeInRangeStatement, AttributeInSetStatement},
+            web3id::v1::{AccountBasedSubjectClaims, ContextInformation},
+        };
+
+        let mut rng = thread_rng();
+        let cred_id = CredentialRegistrationID::new(ArCurve::generate(&mut rng));
+        let other_cred_id = CredentialRegistrationID::new(ArCurve::generate(&mut rng));
+        let text = |s: &str| Web3IdAttribute::String(AttributeKind::try_new(s.into()).unwrap());
+        let request = RequestV1::<ArCurve, Web3IdAttribute> {
+            context: ContextInformation {
+                given: Vec::new(),
+                requested: Vec::new(),
+            },
+            subject_claims: vec![SubjectClaims::Account(AccountBasedSubjectClaims {
+                network: Network::Testnet,
+                issuer: IpIdentity(0),
+                cred_id,
+                statements: vec![
+                    AtomicStatementV1::AttributeInRange(AttributeInRangeStatement {
+                        attribute_tag: AttributeTag(3),
+                        lower: text("18000101"),
+                        upper: text("20000101"),
+                        _phantom: Default::default(),
+                    }),
+                    AtomicStatementV1::AttributeInSet(AttributeInSetStatement {
+                        attribute_tag: AttributeTag(5),
+                        set: [text("DE"), text("DK")].into(),
+                        _phantom: Default::default(),
+                    }),
+                ],
+            })],
+        };
+        let candidate = |issuer: u32, cred_id: CredentialRegistrationID, nationality: &str| {
+            json!({
+                "credId": cred_id,
+                "inputs": {
+                    "type": "account",
+                    "issuer": issuer,
+                    "values": { "dob": "19950101", "nationality": nationality },
+                    "randomness": {},
+                },
+            })
+        };
+        let input = from_value(json!({
+            "request": request,
+            "candidates": [
+                candidate(0, cred_id, "FR"),
+                candidate(0, other_cred_id, "DK"),
+                candidate(1, cred_id, "DK"),
+                candidate(0, cred_id, "DK"),
+            ],
+            "verificationTime": "2021-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        let report: SerdeValue =
+            from_str(&check_presentation_satisfiability_aux(input).unwrap()).unwrap();
+
+        assert_eq!(report["satisfiable"], json!(true));
+        let candidates = &report["subjectClaims"][0]["candidates"];
+        let wrong_nationality = &candidates[0];
+        assert_eq!(wrong_nationality["satisfied"], json!(false));
+        assert_eq!(wrong_nationality["statements"][0]["satisfied"], json!(true));
+        assert_eq!(
+            wrong_nationality["statements"][1]["satisfied"],
+            json!(false)
+        );
+        assert_eq!(
+            wrong_nationality["statements"][1]["reason"],
+            json!("The attribute is not in the set")
+        );
+        for mismatched in [&candidates[1], &candidates[2]] {
+            assert_eq!(mismatched["satisfied"], json!(false));
+            assert!(mismatched["reason"].is_string());
+            assert_eq!(mismatched["statements"], json!([]));
+        }
+        assert_eq!(candidates[3]["satisfied"], json!(true));
+    }
+
+    #[test]
+    fn presentation_satisfiability_at_the_verification_time() {
+        use concordium_base::web3id::v1::{ContextInformation, IdentityBasedSubjectClaims};
+
+        // The attribute list of the test identity is valid from May 2020 to
+        // May 2022.
+        let identity = test_identity();
+        let request = RequestV1::<ArCurve, Web3IdAttribute> {
+            context: ContextInformation {
+                given: Vec::new(),
+                requested: Vec::new(),
+            },
+            subject_claims: vec![SubjectClaims::Identity(IdentityBasedSubjectClaims {
+                network: Network::Testnet,
+                issuer: identity.ip_info.ip_identity,
+                statements: Vec::new(),
+            })],
+        };
+        let check = |verification_time: &str| {
+            let input = from_value(json!({
+                "request": request,
+                "candidates": [{
+                    "inputs": {
+                        "type": "identity",
+                        "ipInfo": identity.ip_info,
+                        "arsInfos": identity.ars_infos,
+                        "idObject": identity.id_object,
+                        "idObjectUseData": identity.id_use_data,
+                    },
+                }],
+                "verificationTime": verification_time,
+            }))
+            .unwrap();
+            let report: SerdeValue =
+                from_str(&check_presentation_satisfiability_aux(input).unwrap()).unwrap();
+            report["subjectClaims"][0]["candidates"][0].clone()
+        };
+
+        assert_eq!(check("2021-01-01T00:00:00Z")["satisfied"], json!(true));
+        let expired = check("2022-06-01T00:00:00Z");
+        assert_eq!(expired["satisfied"], json!(false));
+        assert_eq!(
+            expired["reason"],
+            json!("The identity expired after 202205")
+        );
+        let not_valid_yet = check("2020-04-30T23:59:59Z");
+        assert_eq!(not_valid_yet["satisfied"], json!(false));
+        assert_eq!(
+            not_valid_yet["reason"],
+            json!("The identity is not valid until 202005")
+        );
+    }
+
+    #[test]
+    fn presentation_policy_verdicts() {
+        use concordium_base::{
+            id::id_proof_types::AttributeInRangeStatement,
+            web3id::v1::{
+                AccountBasedSubjectClaims, AccountCredentialVerificationMaterial,
+                ContextInformation, OwnedAccountCredentialProofPrivateInputs,
+            },
+        };
+
+        let mut rng = thread_rng();
+        let global = GlobalContext::<ArCurve>::generate("Test".into());
+        let text = |s: &str| Web3IdAttribute::String(AttributeKind::try_new(s.into()).unwrap());
+        let dob = text("19950101");
+        let (commitment, randomness) = global.on_chain_commitment_key.commit(
+            &PedersenValue::<ArCurve>::new(dob.to_field_element()),
+            &mut rng,
+        );
+        let inputs: OwnedCredentialProofPrivateInputs<
+            constants::IpPairing,
+            ArCurve,
+            Web3IdAttribute,
+        > = OwnedCredentialProofPrivateInputs::Account(OwnedAccountCredentialProofPrivateInputs {
+            issuer: IpIdentity(0),
+            attribute_values: [(AttributeTag(3), dob)].into(),
+            attribute_randomness: [(AttributeTag(3), randomness)].into(),
+        });
+        let public_data = CredentialVerificationMaterial::<constants::IpPairing, ArCurve>::Account(
+            AccountCredentialVerificationMaterial {
+                issuer: IpIdentity(0),
+                attribute_commitments: [(AttributeTag(3), commitment)].into(),
+            },
+        );
+        let claims = SubjectClaims::Account(AccountBasedSubjectClaims {
+            network: Network::Testnet,
+            issuer: IpIdentity(0),
+            cred_id: CredentialRegistrationID::new(ArCurve::generate(&mut rng)),
+            statements: vec![AtomicStatementV1::AttributeInRange(
+                AttributeInRangeStatement {
+                    attribute_tag: AttributeTag(3),
+                    lower: text("18000101"),
+                    upper: text("20000101"),
+                    _phantom: Default::default(),
+                },
+            )],
+        });
+        let request = |subject_claims| RequestV1::<ArCurve, Web3IdAttribute> {
+            context: ContextInformation {
+                given: Vec::new(),
+                requested: Vec::new(),
+            },
+            subject_claims,
+        };
+        let presentation = request(vec![claims.clone()])
+            .prove(&global, [inputs.borrow()].into_iter())
+            .unwrap();
+
+        let verify_trusting =
+            |request: RequestV1<ArCurve, Web3IdAttribute>, status: &str, trusted: SerdeValue| {
+                let input = from_value(json!({
+                    "presentation": presentation,
+                    "request": request,
+                    "globalContext": global,
+                    "publicData": [public_data],
+                    "verificationTime": "2030-01-01T00:00:00Z",
+                    "trustedIdentityProviders": trusted,
+                    "statuses": [status],
+                }))
+                .unwrap();
+                from_str::<SerdeValue>(&verify_presentation_policy_aux(input).unwrap()).unwrap()
+            };
+        let verify =
+            |request, status| verify_trusting(request, status, json!(["did:ccd:testnet:idp:0"]));
+
+        let verdict = verify(request(vec![claims.clone()]), "Active");
+        assert_eq!(verdict["isValid"], json!(true));
+        assert_eq!(
+            verdict["credentials"][0]["issuer"],
+            json!("did:ccd:testnet:idp:0")
+        );
+
+        // Identity providers are only trusted on their own network.
+        let verdict = verify_trusting(
+            request(vec![claims.clone()]),
+            "Active",
+            json!(["did:ccd:mainnet:idp:0", "did:ccd:testnet:idp:1"]),
+        );
+        assert_eq!(verdict["isValid"], json!(false));
+        assert_eq!(
+            verdict["credentials"][0]["reasons"],
+            json!(["The issuer did:ccd:testnet:idp:0 is not trusted"])
+        );
+
+        let verdict = verify(request(vec![claims.clone()]), "Revoked");
+        assert_eq!(verdict["isValid"], json!(false));
+        assert_eq!(
+            verdict["credentials"][0]["reasons"],
+            json!(["The credential is revoked"])
+        );
+
+        let verdict = verify(request(vec![claims.clone(), claims]), "Active");
+        assert_eq!(verdict["isValid"], json!(false));
+        assert_eq!(
+            verdict["reason"],
+            json!("The presentation has 1 credentials, but 2 were requested")
+        );
+        assert_eq!(verdict["credentials"][0]["isValid"], json!(true));
+        let missing = &verdict["credentials"][1];
+        assert_eq!(missing["isValid"], json!(false));
+        assert_eq!(
+            missing["reasons"],
+            json!(["The presentation has no credential for the requested claims"])
+        );
+        assert_eq!(missing["statements"][0]["isValid"], json!(false));
+    }
+
+    #[test]
+    fn json_to_cbor_value_floats() {
+        let cases = [
+            ("0.0", "00"),
+            ("-0.0", "00"),
+            ("1", "01"),
+            ("1.0", "01"),
+            ("-1.0", "20"),
+            ("65504.0", "19ffe0"),
+            ("100000.0", "1a000186a0"),
+            ("1.5", "f93e00"),
+            ("-1.5", "f9be00"),
+            ("0.00006103515625", "f90400"),
+            ("5.960464477539063e-8", "f90001"),
+            ("0.000030517578125", "f90200"),
+            ("3.4028234663852886e38", "fa7f7fffff"),
+            ("1.0e300", "fb7e37e43c8800759c"),
+            ("1.1", "fb3ff199999999999a"),
+            ("-4.1", "fbc010666666666666"),
+            ("0.1", "fb3fb999999999999a"),
+            ("1.00000011920928955078125", "fa3f800001"),
+        ];
+        for (json, encoded) in cases {
+            let value = json_to_cbor_value(&from_str(json).unwrap()).unwrap();
+            assert_eq!(
+                hex::encode(cbor::cbor_encode(&value).unwrap()),
+                encoded,
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn json_to_cbor_value_items() {
+        let encode = |json: SerdeValue| {
+            json_to_cbor_value(&json).map(|value| hex::encode(cbor::cbor_encode(&value).unwrap()))
+        };
+        assert_eq!(encode(json!("hello")).unwrap(), "6568656c6c6f");
+        assert_eq!(encode(json!("0102")).unwrap(), "6430313032");
+        assert_eq!(encode(json!({ "$bytes": "0102" })).unwrap(), "420102");
+        assert_eq!(encode(json!({ "$cbor": "6161" })).unwrap(), "6161");
+        assert_eq!(encode(json!(-2)).unwrap(), "21");
+        // Map keys are sorted by their encoding, so shorter keys come first.
+        assert_eq!(
+            encode(json!({ "aa": 1, "b": [true, null] })).unwrap(),
+            "a2616282f5f662616101"
+        );
+        // Objects with more than one key are maps, even if the keys are tags.
+        assert_eq!(
+            encode(json!({ "$bytes": "0102", "$cbor": "01" })).unwrap(),
+            "a2652463626f72623031662462797465736430313032"
+        );
+        assert!(encode(json!({ "$bytes": "xyz" })).is_err());
+        assert!(encode(json!({ "$cbor": "xyz" })).is_err());
+        assert!(encode(json!({ "$cbor": "1a00" })).is_err());
+    }
+
+    #[test]
+    fn validate_statements_duplicate_attributes() {
+        let problems = |statement: SerdeValue| {
+            let input = from_value(json!({ "statement": statement })).unwrap();
+            let out: SerdeValue = from_str(&validate_statements_aux(input).unwrap()).unwrap();
+            assert_eq!(out["isValid"], json!(false));
+            out["problems"].clone()
+        };
+        let reveal = json!({ "type": "RevealAttribute", "attributeTag": "firstName" });
+        let in_set =
+            json!({ "type": "AttributeInSet", "attributeTag": "firstName", "set": ["Alice"] });
+
+        assert_eq!(
+            problems(json!([reveal, reveal])),
+            json!([{
+                "path": "statement[1]",
+                "message": "Only one statement is allowed for the attribute firstName",
+            }])
+        );
+        assert_eq!(
+            problems(json!([in_set, in_set])),
+            json!([{
+                "path": "statement[1]",
+                "message": "Only one statement is allowed for the attribute firstName",
+            }])
+        );
+        for statement in [json!([reveal, in_set]), json!([in_set, reveal])] {
+            assert_eq!(
+                problems(statement),
+                json!([{
+                    "path": "statement[1]",
+                    "message": "The attribute firstName is both revealed and proven",
+                }])
+            );
+        }
+    }
+
+    #[test]
+    fn validate_statements_into_typed_statements() {
+        use concordium_base::web3id::v1::IdentityBasedSubjectClaims;
+
+        let validate = |input: SerdeValue| -> SerdeValue {
+            from_str(&validate_statements_aux(from_value(input).unwrap()).unwrap()).unwrap()
+        };
+        let claims = |statement: SerdeValue| {
+            let mut claims = json!(SubjectClaims::<ArCurve, Web3IdAttribute>::Identity(
+                IdentityBasedSubjectClaims {
+                    network: Network::Testnet,
+                    issuer: IpIdentity(0),
+                    statements: Vec::new(),
+                }
+            ));
+            claims["statement"] = statement;
+            json!({ "subjectClaims": [claims] })
+        };
+        let dob = |lower: SerdeValue, upper: SerdeValue| json!({ "type": "AttributeInRange", "attributeTag": "dob", "lower": lower, "upper": upper });
+        let nationality = |set: SerdeValue| json!({ "type": "AttributeInSet", "attributeTag": "nationality", "set": set });
+
+        // The normalized statements are the typed statements of the SDK.
+        let out = validate(json!({
+            "statement": [
+                dob(json!("18000101"), json!("20000101")),
+                nationality(json!(["DK", "DE", "DK"])),
+            ],
+        }));
+        assert_eq!(out["isValid"], json!(true));
+        assert_eq!(out["statement"][1]["set"], json!(["DE", "DK"]));
+        from_value::<Statement<ArCurve, AttributeKind>>(out["statement"].clone()).unwrap();
+        let out = validate(claims(json!([
+            dob(json!("18000101"), json!("20000101")),
+            nationality(json!(["DK", "DE", "DK"])),
+        ])));
+        assert_eq!(out["isValid"], json!(true));
+        assert_eq!(
+            out["subjectClaims"][0]["statement"][1]["set"],
+            json!(["DE", "DK"])
+        );
+        from_value::<Vec<SubjectClaims<ArCurve, Web3IdAttribute>>>(out["subjectClaims"].clone())
+            .unwrap();
+
+        // Only the statements of the SDK type are allowed.
+        let problems = |input: SerdeValue| {
+            let out = validate(input);
+            assert_eq!(out["isValid"], json!(false));
+            assert!(out.get("statement").is_none());
+            assert!(out.get("subjectClaims").is_none());
+            out["problems"].as_array().unwrap().clone()
+        };
+        let reveal = json!({ "type": "RevealAttribute", "attributeTag": "firstName" });
+        let value =
+            json!({ "type": "AttributeValue", "attributeTag": "firstName", "attributeValue": "A" });
+        for (input, path) in [
+            (claims(json!([reveal])), "subjectClaims[0].statement[0]"),
+            (json!({ "statement": [value] }), "statement[0]"),
+        ] {
+            let problems = problems(input);
+            assert_eq!(problems.len(), 1);
+            assert_eq!(problems[0]["path"], json!(path));
+            assert!(problems[0]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Malformed statement"));
+        }
+
+        // Values of different types are neither equal nor ordered.
+        assert_eq!(
+            problems(claims(json!([nationality(json!(["DK", 5]))]))),
+            vec![json!({
+                "path": "subjectClaims[0].statement[0]",
+                "message": "The set contains values of different types",
+            })]
+        );
+        assert!(
+            problems(claims(json!([dob(json!("18000101"), json!(20000101))]))).contains(&json!({
+                "path": "subjectClaims[0].statement[0]",
+                "message": "The bounds of the range have different types",
+            }))
+        );
+
+        // The bounds are ordered by their embedding, as in the proofs.
+        assert_eq!(
+            problems(claims(json!([dob(json!("20000101"), json!("18000101"))]))),
+            vec![json!({
+                "path": "subjectClaims[0].statement[0]",
+                "message": "The lower bound must be less than the upper bound",
+            })]
+        );
+        let lower = Web3IdAttribute::String(AttributeKind::try_new("Z".into()).unwrap());
+        let upper = Web3IdAttribute::String(AttributeKind::try_new("AA".into()).unwrap());
+        assert!(embedded_attribute(&lower) < embedded_attribute(&upper));
+    }
+
+    /// The accounts, keys and signatures of the `signMessage` tests of the SDK,
+    /// which sign the message "test".
+    const SIGN_MESSAGE_ACCOUNT_SINGLE: &str = "3eP94feEdmhYiPC1333F9VoV31KGMswonuHk5tqmZrzf761zK5";
+    const SIGN_MESSAGE_SIGNATURE_SINGLE: &str = "445197d79ca90d8cc8440328dac9f307932ade0c03cc7aa575b59b746e26e5f1bca13ade5ff7a56e918ba5a32450fdf52b034cd2580929b21213263e81f7f809";
+    const SIGN_MESSAGE_ACCOUNT_MULTI: &str = "4hTGW1Uz6u2hUgEtwWjJUdZQncVpHGWZPgGdRpgL1VNn5NzyHd";
+    const SIGN_MESSAGE_SIGNATURES_MULTI: [&str; 3] = [
+        "37798d551f26f48496a3d14aee0d29f5bb6a1dc99a75c06b5a8be4f901ba8e6e7c32a7461bd419f481115e647a43d43075f0ccb000627eaa2329eed81582fc02",
+        "36fc3a13869535a934adb61809b010dd015126920c24032dfcde1c3883151bc61219f2582564f1e13d743a34ce762925d6171685a1fec62e1cbf731e551a430f",
+        "024c91adf278e9018f27546da73acf865823989b2385dd9575743c1390dda1afa47e85894ac2324bd9cd5459393b69a18787c18262ac90d65b404245491c6b0c",
+    ];
+    const SIGN_MESSAGE_VERIFY_KEYS_MULTI: [&str; 3] = [
+        "008739a5c6708b25c359d45179fefda7ef1345099c0ad8e9b66ed253d968098d",
+        "45b55ad7438cb72c06489be231443cb3b7708f9b3f770729e2092f78ea9e2d9d",
+        "ed2f710f6edbf65806eaee6d643a12124332a6dc687be099b63fd0150294168d",
+    ];
+
+    fn message_account_info(address: &str, verify_keys: &[&str], threshold: u8) -> SerdeValue {
+        let keys: serde_json::Map<String, SerdeValue> = verify_keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                (
+                    i.to_string(),
+                    json!({ "schemeId": "Ed25519", "verifyKey": key }),
+                )
+            })
+            .collect();
+        json!({
+            "accountAddress": address,
+            "accountThreshold": 1,
+            "accountCredentials": {
+                "0": { "value": { "contents": { "credentialPublicKeys": {
+                    "keys": keys,
+                    "threshold": threshold,
+                } } } },
+            },
+        })
+    }
+
+    #[test]
+    fn sign_and_verify_message() {
+        let message = hex::encode("test");
+        let input = from_value(json!({
+            "address": SIGN_MESSAGE_ACCOUNT_SINGLE,
+            "message": message,
+            "keys": {
+                "keys": { "0": { "keys": { "0": {
+                    "signKey": "e1cf504954663e49f4fe884c7c35415b09632cccd82d3d2a62ab2825e67d785d",
+                    "verifyKey": "fef5414fc757cd4694bf0c7ea436f015cb7f87a80d08e1d1085b9cc91f13f376",
+                } }, "threshold": 1 } },
+                "threshold": 1,
+            },
+        }))
+        .unwrap();
+        let signature: SerdeValue = from_str(&sign_message_aux(input).unwrap()).unwrap();
+        // Ed25519 signatures are deterministic, so this also checks that the
+        // digest is the one computed by `getMessageDigest` in the SDK.
+        assert_eq!(
+            signature,
+            json!({ "0": { "0": SIGN_MESSAGE_SIGNATURE_SINGLE } })
+        );
+
+        let verify = |address: &str, signature: SerdeValue| {
+            let input = from_value(json!({
+                "message": message,
+                "signature": signature,
+                "accountInfo": message_account_info(
+                    address,
+                    &["fef5414fc757cd4694bf0c7ea436f015cb7f87a80d08e1d1085b9cc91f13f376"],
+                    1,
+                ),
+            }))
+            .unwrap();
+            from_str::<SerdeValue>(&verify_message_signature_aux(input).unwrap()).unwrap()
+        };
+        assert_eq!(
+            verify(SIGN_MESSAGE_ACCOUNT_SINGLE, signature.clone())["isValid"],
+            json!(true)
+        );
+        assert_eq!(
+            verify(SIGN_MESSAGE_ACCOUNT_MULTI, signature)["isValid"],
+            json!(false)
+        );
+    }
+
+    #[test]
+    fn verify_message_signature_threshold_and_malformed_signatures() {
+        let verify = |signatures: SerdeValue| {
+            from_value::<VerifyMessageSignatureInput>(json!({
+                "message": hex::encode("test"),
+                "signature": { "0": signatures },
+                "accountInfo": message_account_info(
+                    SIGN_MESSAGE_ACCOUNT_MULTI,
+                    &SIGN_MESSAGE_VERIFY_KEYS_MULTI,
+                    3,
+                ),
+            }))
+            .unwrap()
+        };
+        let [sig0, sig1, sig2] = SIGN_MESSAGE_SIGNATURES_MULTI;
+        let inputs = vec![
+            verify(json!({ "0": sig0, "1": sig1, "2": sig2 })),
+            verify(json!({ "0": sig0, "1": sig1 })),
+            verify(json!({ "0": sig0, "1": sig1, "2": "not hex" })),
+        ];
+        let reports: SerdeValue =
+            from_str(&verify_message_signatures_aux(inputs).unwrap()).unwrap();
+
+        assert_eq!(reports[0]["isValid"], json!(true));
+        assert_eq!(reports[1]["isValid"], json!(false));
+        assert_eq!(reports[1]["credentials"]["0"]["validKeys"], json!([0, 1]));
+        assert_eq!(reports[1]["credentials"]["0"]["thresholdMet"], json!(false));
+        assert_eq!(reports[2]["isValid"], json!(false));
+        assert_eq!(reports[2]["credentials"]["0"]["invalidKeys"], json!([2]));
+    }
+
+    #[test]
+    fn structured_message_round_trip() {
+        use concordium_base::contracts_common::schema::{Fields, SizeLength};
+
+        let schema = Type::Struct(Fields::Named(vec![
+            ("name".into(), Type::String(SizeLength::U8)),
+            ("amount".into(), Type::U64),
+        ]));
+        let schema = hex::encode(contracts_common::to_bytes(&schema));
+        let create = |value: SerdeValue| {
+            let input = from_value(json!({
+                "address": SIGN_MESSAGE_ACCOUNT_SINGLE,
+                "value": value,
+                "schema": schema,
+                "keys": {
+                    "keys": { "0": { "keys": { "0": {
+                        "signKey": "e1cf504954663e49f4fe884c7c35415b09632cccd82d3d2a62ab2825e67d785d",
+                        "verifyKey": "fef5414fc757cd4694bf0c7ea436f015cb7f87a80d08e1d1085b9cc91f13f376",
+                    } }, "threshold": 1 } },
+                    "threshold": 1,
+                },
+            }))
+            .unwrap();
+            create_structured_message_aux(input)
+        };
+        let created: SerdeValue =
+            from_str(&create(json!({ "name": "Alice", "amount": 42 })).unwrap()).unwrap();
+        assert_eq!(created["message"], json!("05416c6963652a00000000000000"));
+
+        let verify = |value: SerdeValue| {
+            let input = from_value(json!({
+                "value": value,
+                "schema": schema,
+                "signature": created["signature"],
+                "accountInfo": message_account_info(
+                    SIGN_MESSAGE_ACCOUNT_SINGLE,
+                    &["fef5414fc757cd4694bf0c7ea436f015cb7f87a80d08e1d1085b9cc91f13f376"],
+                    1,
+                ),
+            }))
+            .unwrap();
+            let report: SerdeValue =
+                from_str(&verify_structured_message_signature_aux(input).unwrap()).unwrap();
+            report["isValid"].clone()
+        };
+        assert_eq!(
+            verify(json!({ "name": "Alice", "amount": 42 })),
+            json!(true)
+        );
+        assert_eq!(
+            verify(json!({ "name": "Alice", "amount": 43 })),
+            json!(false)
+        );
+
+        assert!(create(json!({ "name": 5, "amount": 42 })).is_err());
+        assert!(create(json!({ "name": "Alice" })).is_err());
+    }
+
+    #[test]
+    fn energy_costs() {
+        use concordium_base::transactions::{ConfigureBakerPayload, Memo};
+
+        let mut rng = thread_rng();
+        let address: AccountAddress = SIGN_MESSAGE_ACCOUNT_SINGLE.parse().unwrap();
+        let cost =
+            |payload: &[u8], protocol_version: u64, num_existing_credentials: Option<u16>| {
+                let input = from_value(json!({
+                    "payload": hex::encode(payload),
+                    "signatureCount": 1,
+                    "protocolVersion": protocol_version,
+                    "numExistingCredentials": num_existing_credentials,
+                }))
+                .unwrap();
+                get_energy_cost_aux(input)
+                    .map(|out| from_str::<SerdeValue>(&out).unwrap()["energy"].clone())
+            };
+
+        // The energy is the size of the transaction, i.e. the 60 byte header
+        // and the payload, plus 100 per signature and the specific cost.
+        let transfer = to_bytes(&Payload::Transfer {
+            to_address: address,
+            amount: Amount::from_micro_ccd(1),
+        });
+        assert_eq!(transfer.len(), 41);
+        assert_eq!(cost(&transfer, 1, None).unwrap(), json!(501));
+
+        let transfer_with_memo = to_bytes(&Payload::TransferWithMemo {
+            to_address: address,
+            memo: Memo::try_from(b"hello".to_vec()).unwrap(),
+            amount: Amount::from_micro_ccd(1),
+        });
+        assert_eq!(transfer_with_memo.len(), 48);
+        assert!(cost(&transfer_with_memo, 1, None).is_err());
+        assert_eq!(cost(&transfer_with_memo, 2, None).unwrap(), json!(508));
+
+        let mut without_keys = ConfigureBakerPayload::new();
+        without_keys.set_capital(Amount::from_ccd(20000));
+        let without_keys = to_bytes(&Payload::ConfigureBaker {
+            data: Box::new(without_keys),
+        });
+        assert_eq!(without_keys.len(), 11);
+        assert!(cost(&without_keys, 3, None).is_err());
+        assert_eq!(cost(&without_keys, 7, None).unwrap(), json!(471));
+
+        let mut with_keys = ConfigureBakerPayload::new();
+        with_keys.add_keys(&BakerKeyPairs::generate(&mut rng), address, &mut rng);
+        let with_keys = to_bytes(&Payload::ConfigureBaker {
+            data: Box::new(with_keys),
+        });
+        assert_eq!(with_keys.len(), 355);
+        assert_eq!(cost(&with_keys, 7, None).unwrap(), json!(4565));
+
+        // Removing a credential from an account with two credentials costs 500
+        // plus 500 per existing credential.
+        let update_credentials = to_bytes(&Payload::UpdateCredentials {
+            new_cred_infos: BTreeMap::new(),
+            remove_cred_ids: vec![CredentialRegistrationID::new(ArCurve::generate(&mut rng))],
+            new_threshold: AccountThreshold::try_from(1u8).unwrap(),
+        });
+        assert_eq!(update_credentials.len(), 52);
+        assert!(cost(&update_credentials, 7, None).is_err());
+        assert_eq!(cost(&update_credentials, 7, Some(2)).unwrap(), json!(1712));
+
+        let mut padded = transfer.clone();
+        padded.push(0);
+        assert!(cost(&padded, 7, None).is_err());
+    }
+
+    #[test]
+    fn energy_to_micro_ccd_rounds_up() {
+        let rate = |numerator, denominator| ExchangeRateInput {
+            numerator,
+            denominator,
+        };
+        let micro_ccd = |energy, euro_per_energy, micro_ccd_per_euro| {
+            energy_to_micro_ccd(Energy { energy }, &euro_per_energy, &micro_ccd_per_euro)
+                .unwrap()
+                .micro_ccd()
+        };
+        // 501 * 100000000 / (50000 * 3) = 334000 exactly.
+        assert_eq!(micro_ccd(501, rate(1, 50000), rate(100000000, 3)), 334000);
+        // 501 * 100000000 / (50000 * 7) = 143142.857...
+        assert_eq!(micro_ccd(501, rate(1, 50000), rate(100000000, 7)), 143143);
+        assert_eq!(micro_ccd(1, rate(1, 3), rate(1, 1)), 1);
+        assert_eq!(micro_ccd(0, rate(1, 3), rate(1, 1)), 0);
+        assert!(energy_to_micro_ccd(Energy { energy: 1 }, &rate(1, 0), &rate(1, 1)).is_err());
+    }
+
+    #[test]
+    fn energy_cost_in_micro_ccd_from_json() {
+        let address: AccountAddress = SIGN_MESSAGE_ACCOUNT_SINGLE.parse().unwrap();
+        let transfer = to_bytes(&Payload::Transfer {
+            to_address: address,
+            amount: Amount::from_micro_ccd(1),
+        });
+        let cost = |rates: SerdeValue| {
+            let mut input = json!({
+                "payload": hex::encode(&transfer),
+                "signatureCount": 1,
+                "protocolVersion": 7,
+            });
+            input
+                .as_object_mut()
+                .unwrap()
+                .extend(rates.as_object().unwrap().clone());
+            get_energy_cost_aux(from_value(input).unwrap())
+                .map(|out| from_str::<SerdeValue>(&out).unwrap())
+        };
+
+        let euro_per_energy = json!({ "numerator": 1, "denominator": 50000 });
+        let micro_ccd_per_euro = json!({ "numerator": 100000000, "denominator": 7 });
+        assert_eq!(
+            cost(json!({
+                "euroPerEnergy": euro_per_energy,
+                "microCCDPerEuro": micro_ccd_per_euro,
+            }))
+            .unwrap(),
+            json!({ "energy": 501, "microCcd": "143143" })
+        );
+        assert_eq!(
+            cost(json!({
+                "euroPerEnergy": euro_per_energy,
+                "microCcdPerEuro": micro_ccd_per_euro,
+            }))
+            .unwrap(),
+            json!({ "energy": 501, "microCcd": "143143" })
+        );
+        assert_eq!(
+            cost(json!({})).unwrap(),
+            json!({ "energy": 501, "microCcd": null })
+        );
+        assert!(cost(json!({ "euroPerEnergy": euro_per_energy })).is_err());
+        assert!(cost(json!({ "microCCDPerEuro": micro_ccd_per_euro })).is_err());
+    }
+}
@@ -5,6 +5,8 @@ use anyhow::Context;
 use concordium_base::{
     self as base,
     common::cbor::{self, cbor_encode},
+    contracts_common::ContractAddress,
+    elgamal::BabyStepGiantStep,
     id::{
         constants::{ArCurve, IpPairing},
         types::GlobalContext,
@@ -12,8 +14,9 @@ use concordium_base::{
     web3id::{
         v1::{
             anchor::{
-                RequestedSubjectClaims, UnfilledContextInformation, VerificationAuditRecord,
-                VerificationRequestData,
+                RequestedSubjectClaims, UnfilledContextInformation, VerifiablePresentationV1,
+                VerificationAuditAnchor, VerificationAuditRecord, VerificationRequest,
+                VerificationRequestAnchor, VerificationRequestData,
             },
             CredentialVerificationMaterial, PresentationV1,
         },
@@ -28,15 +31,9 @@ use serde::de;
 use wallet_library::{
     credential::create_unsigned_credential_v1_aux,
     identity::{create_identity_object_request_v1_aux, create_identity_recovery_request_aux},
-    wallet::{
-        get_account_public_key_aux, get_account_signing_key_aux,
-        get_attribute_commitment_randomness_aux, get_credential_id_aux, get_id_cred_sec_aux,
-        get_prf_key_aux, get_signature_blinding_randomness_aux,
-        get_verifiable_credential_backup_encryption_key_aux,
-        get_verifiable_credential_public_key_aux, get_verifiable_credential_signing_key_aux,
-    },
 };
 use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
 
 #[wasm_bindgen(js_name = generateUnsignedCredential)]
 pub fn generate_unsigned_credential_ext(input: &str) -> JsResult {
@@ -75,30 +72,71 @@ pub fn get_credential_deployment_info_ext(signatures: &JsValue, unsigned_info: &
 
 #[wasm_bindgen(js_name = createIdRequestV1)]
 pub fn create_id_request_v1_ext(input: JsonString) -> JsResult {
-    create_identity_object_request_v1_aux(serde_json::from_str(&input).unwrap())
+    let input = serde_json::from_str(&input)?;
+    with_id_request_secrets(None, input)
+        .and_then(|input| Ok(serde_json::from_value(input)?))
+        .and_then(create_identity_object_request_v1_aux)
         .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = createIdentityRecoveryRequest)]
 pub fn create_identity_recovery_request_ext(input: JsonString) -> JsResult {
-    create_identity_recovery_request_aux(serde_json::from_str(&input).unwrap()).map_err(to_js_error)
+    let input = serde_json::from_str(&input)?;
+    with_identity_recovery_secrets(None, input)
+        .and_then(|input| Ok(serde_json::from_value(input)?))
+        .and_then(create_identity_recovery_request_aux)
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = createCredentialV1)]
 pub fn create_credential_v1_ext(raw_input: JsonString) -> JsResult {
+    let seed: SeedInput = serde_json::from_str(&raw_input)?;
+    let input = serde_json::from_str(&raw_input)?;
+    seed.wallet()
+        .and_then(|wallet| create_credential_v1_aux(&wallet, input))
+        .map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = createExistingAccountCredentialV1)]
+pub fn create_existing_account_credential_v1_ext(raw_input: JsonString) -> JsResult {
+    let seed: SeedInput = serde_json::from_str(&raw_input)?;
     let input = serde_json::from_str(&raw_input)?;
-    create_credential_v1_aux(input).map_err(to_js_error)
+    seed.wallet()
+        .and_then(|wallet| create_existing_account_credential_v1_aux(&wallet, input))
+        .map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = verifyIdentityObject)]
+pub fn verify_identity_object_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    with_id_request_secrets(None, input)
+        .and_then(|input| Ok(serde_json::from_value(input)?))
+        .and_then(verify_identity_object_aux)
+        .map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = verifyCredentialDeployment)]
+pub fn verify_credential_deployment_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    verify_credential_deployment_aux(input).map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = createUnsignedCredentialV1)]
 pub fn create_unsigned_credential_v1_ext(input: JsonString) -> JsResult {
-    create_unsigned_credential_v1_aux(serde_json::from_str(&input).unwrap()).map_err(to_js_error)
+    let input = serde_json::from_str(&input)?;
+    with_unsigned_credential_secrets(None, input)
+        .and_then(|input| Ok(serde_json::from_value(input)?))
+        .and_then(create_unsigned_credential_v1_aux)
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = createIdProof)]
 pub fn create_id_proof_ext(raw_input: JsonString) -> JsResult {
+    let seed: SeedInput = serde_json::from_str(&raw_input)?;
     let input = serde_json::from_str(&raw_input)?;
-    create_id_proof_aux(input).map_err(to_js_error)
+    seed.wallet()
+        .and_then(|wallet| create_id_proof_aux(&wallet, input))
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = getAccountSigningKey)]
@@ -109,14 +147,16 @@ pub fn get_account_signing_key_ext(
     identity_index: u32,
     credential_counter: u32,
 ) -> JsResult<HexString> {
-    get_account_signing_key_aux(
-        seed_as_hex,
-        raw_net,
-        identity_provider_index,
-        identity_index,
-        credential_counter,
-    )
-    .map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| {
+            account_signing_key_aux(
+                &wallet,
+                identity_provider_index,
+                identity_index,
+                credential_counter,
+            )
+        })
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = getAccountPublicKey)]
@@ -127,14 +167,16 @@ pub fn get_account_public_key_ext(
     identity_index: u32,
     credential_counter: u32,
 ) -> JsResult<HexString> {
-    get_account_public_key_aux(
-        seed_as_hex,
-        raw_net,
-        identity_provider_index,
-        identity_index,
-        credential_counter,
-    )
-    .map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| {
+            account_public_key_aux(
+                &wallet,
+                identity_provider_index,
+                identity_index,
+                credential_counter,
+            )
+        })
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = deserializeCredentialDeployment)]
@@ -151,15 +193,26 @@ pub fn get_credential_id_ext(
     credential_counter: u8,
     raw_on_chain_commitment_key: &str,
 ) -> JsResult<HexString> {
-    get_credential_id_aux(
-        seed_as_hex,
-        raw_net,
-        identity_provider_index,
-        identity_index,
-        credential_counter,
-        raw_on_chain_commitment_key,
-    )
-    .map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| {
+            credential_id_aux(
+                &wallet,
+                identity_provider_index,
+                identity_index,
+                credential_counter,
+                raw_on_chain_commitment_key,
+            )
+        })
+        .map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = deriveCredentials)]
+pub fn derive_credentials_ext(raw_input: JsonString) -> JsResult {
+    let seed: SeedInput = serde_json::from_str(&raw_input)?;
+    let input = serde_json::from_str(&raw_input)?;
+    seed.wallet()
+        .and_then(|wallet| derive_credentials_aux(&wallet, input))
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = getPrfKey)]
@@ -169,13 +222,9 @@ pub fn get_prf_key_ext(
     identity_provider_index: u32,
     identity_index: u32,
 ) -> JsResult<HexString> {
-    get_prf_key_aux(
-        seed_as_hex,
-        raw_net,
-        identity_provider_index,
-        identity_index,
-    )
-    .map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| prf_key_aux(&wallet, identity_provider_index, identity_index))
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = getIdCredSec)]
@@ -185,13 +234,9 @@ pub fn get_id_cred_sec_ext(
     identity_provider_index: u32,
     identity_index: u32,
 ) -> JsResult<HexString> {
-    get_id_cred_sec_aux(
-        seed_as_hex,
-        raw_net,
-        identity_provider_index,
-        identity_index,
-    )
-    .map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| id_cred_sec_aux(&wallet, identity_provider_index, identity_index))
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = getSignatureBlindingRandomness)]
@@ -201,13 +246,11 @@ pub fn get_signature_blinding_randomness_ext(
     identity_provider_index: u32,
     identity_index: u32,
 ) -> JsResult<HexString> {
-    get_signature_blinding_randomness_aux(
-        seed_as_hex,
-        raw_net,
-        identity_provider_index,
-        identity_index,
-    )
-    .map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| {
+            signature_blinding_randomness_aux(&wallet, identity_provider_index, identity_index)
+        })
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = getAttributeCommitmentRandomness)]
@@ -219,15 +262,17 @@ pub fn get_attribute_commitment_randomness_ext(
     credential_counter: u32,
     attribute: u8,
 ) -> JsResult<HexString> {
-    get_attribute_commitment_randomness_aux(
-        seed_as_hex,
-        raw_net,
-        identity_provider_index,
-        identity_index,
-        credential_counter,
-        attribute,
-    )
-    .map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| {
+            attribute_commitment_randomness_aux(
+                &wallet,
+                identity_provider_index,
+                identity_index,
+                credential_counter,
+                attribute,
+            )
+        })
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = getVerifiableCredentialSigningKey)]
@@ -238,14 +283,15 @@ pub fn get_verifiable_credential_signing_key_ext(
     issuer_subindex: u64,
     verifiable_credential_index: u32,
 ) -> JsResult<HexString> {
-    get_verifiable_credential_signing_key_aux(
-        seed_as_hex,
-        raw_net,
-        issuer_index,
-        issuer_subindex,
-        verifiable_credential_index,
-    )
-    .map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| {
+            verifiable_credential_signing_key_aux(
+                &wallet,
+                ContractAddress::new(issuer_index, issuer_subindex),
+                verifiable_credential_index,
+            )
+        })
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = getVerifiableCredentialPublicKey)]
@@ -256,14 +302,15 @@ pub fn get_verifiable_credential_public_key_ext(
     issuer_subindex: u64,
     verifiable_credential_index: u32,
 ) -> JsResult<HexString> {
-    get_verifiable_credential_public_key_aux(
-        seed_as_hex,
-        raw_net,
-        issuer_index,
-        issuer_subindex,
-        verifiable_credential_index,
-    )
-    .map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| {
+            verifiable_credential_public_key_aux(
+                &wallet,
+                ContractAddress::new(issuer_index, issuer_subindex),
+                verifiable_credential_index,
+            )
+        })
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = getVerifiableCredentialBackupEncryptionKey)]
@@ -271,7 +318,9 @@ pub fn get_verifiable_credential_backup_encryption_key_ext(
     seed_as_hex: HexString,
     raw_net: &str,
 ) -> JsResult<HexString> {
-    get_verifiable_credential_backup_encryption_key_aux(seed_as_hex, raw_net).map_err(to_js_error)
+    wallet_from_hex(&seed_as_hex, raw_net)
+        .and_then(|wallet| verifiable_credential_backup_encryption_key_aux(&wallet))
+        .map_err(to_js_error)
 }
 
 #[wasm_bindgen(js_name = serializeCredentialDeploymentPayload)]
@@ -297,18 +346,462 @@ pub fn generate_baker_keys_ext(sender: Base58String) -> JsResult {
     generate_baker_keys(sender).map_err(to_js_error)
 }
 
+#[wasm_bindgen(js_name = deriveBakerKeys)]
+pub fn derive_baker_keys_ext(raw_input: JsonString) -> JsResult {
+    let seed: SeedInput = serde_json::from_str(&raw_input)?;
+    let input = serde_json::from_str(&raw_input)?;
+    seed.wallet()
+        .and_then(|wallet| derive_baker_keys_aux(&wallet, input))
+        .map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = createBakerCredentialsFile)]
+pub fn create_baker_credentials_file_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    create_baker_credentials_file_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = createConfigureBakerKeysPayload)]
+pub fn create_configure_baker_keys_payload_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    create_configure_baker_keys_payload_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = verifyConfigureBakerKeys)]
+pub fn verify_configure_baker_keys_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    verify_configure_baker_keys_aux(input).map_err(to_js_error)
+}
+
 #[wasm_bindgen(js_name = createWeb3IdProof)]
 pub fn create_web3_id_proof_ext(raw_input: JsonString) -> JsResult {
     let input = serde_json::from_str(&raw_input)?;
     create_web3_id_proof_aux(input).map_err(to_js_error)
 }
 
+#[wasm_bindgen(js_name = verifyIdProof)]
+pub fn verify_id_proof_ext(raw_input: JsonString) -> JsResult<bool> {
+    let input = serde_json::from_str(&raw_input)?;
+    verify_id_proof_aux(input).map_err(to_js_error)
+}
+
 #[wasm_bindgen(js_name = verifyWeb3IdCredentialSignature)]
 pub fn verify_web3_id_credential_signature_ext(raw_input: JsonString) -> JsResult<bool> {
     let input = serde_json::from_str(&raw_input)?;
     verify_web3_id_credential_signature_aux(input).map_err(to_js_error)
 }
 
+#[wasm_bindgen(js_name = signWeb3IdCredential)]
+pub fn sign_web3_id_credential_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    sign_web3_id_credential_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = signMessage)]
+pub fn sign_message_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    sign_message_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = verifyMessageSignature)]
+pub fn verify_message_signature_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    verify_message_signature_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = verifyMessageSignatures)]
+pub fn verify_message_signatures_ext(raw_input: JsonString) -> JsResult {
+    let inputs = serde_json::from_str(&raw_input)?;
+    verify_message_signatures_aux(inputs).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = createStructuredMessage)]
+pub fn create_structured_message_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    create_structured_message_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = verifyStructuredMessageSignature)]
+pub fn verify_structured_message_signature_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    verify_structured_message_signature_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = getEnergyCost)]
+pub fn get_energy_cost_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    get_energy_cost_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = getEncryptionSecretKey)]
+pub fn get_encryption_secret_key_ext(
+    seed_as_hex: HexString,
+    raw_net: &str,
+    identity_provider_index: u32,
+    identity_index: u32,
+    credential_counter: u8,
+    raw_global_context: &str,
+) -> JsResult<HexString> {
+    get_encryption_secret_key_aux(
+        seed_as_hex,
+        raw_net,
+        identity_provider_index,
+        identity_index,
+        credential_counter,
+        raw_global_context,
+    )
+    .map_err(to_js_error)
+}
+
+/// A table for decrypting encrypted amounts. Computing the table is expensive,
+/// so it is kept in wasm memory to be reused for any number of decryptions.
+#[wasm_bindgen]
+pub struct DecryptionTable(BabyStepGiantStep<ArCurve>);
+
+#[wasm_bindgen]
+impl DecryptionTable {
+    #[wasm_bindgen(constructor)]
+    pub fn new(raw_global_context: &str, size: Option<u64>) -> JsResult<DecryptionTable> {
+        create_decryption_table_aux(raw_global_context, size)
+            .map(DecryptionTable)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = decryptAmounts)]
+    pub fn decrypt_amounts(&self, secret_key: HexString, raw_encrypted_amounts: &str) -> JsResult {
+        decrypt_amounts_aux(&self.0, &secret_key, raw_encrypted_amounts).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = createTransferToPublic)]
+    pub fn create_transfer_to_public(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        create_transfer_to_public_aux(&self.0, input).map_err(to_js_error)
+    }
+}
+
+#[wasm_bindgen(js_name = generateMnemonic)]
+pub fn generate_mnemonic_ext(raw_input: JsonString) -> JsResult<String> {
+    let input = serde_json::from_str(&raw_input)?;
+    generate_mnemonic_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = validateMnemonic)]
+pub fn validate_mnemonic_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    validate_mnemonic_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = mnemonicToSeed)]
+pub fn mnemonic_to_seed_ext(raw_input: JsonString) -> JsResult<HexString> {
+    let input = serde_json::from_str(&raw_input)?;
+    mnemonic_to_seed_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = decryptWalletExport)]
+pub fn decrypt_wallet_export_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    decrypt_wallet_export_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = encryptWalletExport)]
+pub fn encrypt_wallet_export_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    encrypt_wallet_export_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = encryptWeb3IdBackup)]
+pub fn encrypt_web3_id_backup_ext(raw_input: JsonString) -> JsResult {
+    let seed: SeedInput = serde_json::from_str(&raw_input)?;
+    let input = serde_json::from_str(&raw_input)?;
+    seed.wallet()
+        .and_then(|wallet| encrypt_web3_id_backup_aux(&wallet, input))
+        .map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = decryptWeb3IdBackup)]
+pub fn decrypt_web3_id_backup_ext(raw_input: JsonString) -> JsResult {
+    let seed: SeedInput = serde_json::from_str(&raw_input)?;
+    let input = serde_json::from_str(&raw_input)?;
+    seed.wallet()
+        .and_then(|wallet| decrypt_web3_id_backup_aux(&wallet, input))
+        .map_err(to_js_error)
+}
+
+/// A hierarchical deterministic wallet which keeps the seed in wasm memory.
+/// The seed is never returned from the wallet, and it is zeroized when the
+/// wallet is freed.
+#[wasm_bindgen]
+pub struct HdWallet(Wallet);
+
+#[wasm_bindgen]
+impl HdWallet {
+    /// Create a wallet from the 64 bytes of the seed. The copy of the bytes in
+    /// wasm memory is zeroized, but the caller should zero the given bytes
+    /// after the wallet has been created.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: Vec<u8>, raw_net: &str) -> JsResult<HdWallet> {
+        let seed = Zeroizing::new(seed);
+        wallet_from_seed(&seed, raw_net)
+            .map(HdWallet)
+            .map_err(to_js_error)
+    }
+
+    /// Create a wallet from a BIP-39 mnemonic and passphrase, such that the
+    /// seed never leaves wasm memory.
+    #[wasm_bindgen(js_name = fromMnemonic)]
+    pub fn from_mnemonic(mnemonic: &str, passphrase: &str, raw_net: &str) -> JsResult<HdWallet> {
+        let seed = mnemonic_to_seed(mnemonic, passphrase).map_err(to_js_error)?;
+        Wallet::new(&seed, raw_net)
+            .map(HdWallet)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getAccountSigningKey)]
+    pub fn get_account_signing_key(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u32,
+    ) -> JsResult<HexString> {
+        account_signing_key_aux(
+            &self.0,
+            identity_provider_index,
+            identity_index,
+            credential_counter,
+        )
+        .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getAccountPublicKey)]
+    pub fn get_account_public_key(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u32,
+    ) -> JsResult<HexString> {
+        account_public_key_aux(
+            &self.0,
+            identity_provider_index,
+            identity_index,
+            credential_counter,
+        )
+        .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getCredentialId)]
+    pub fn get_credential_id(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u8,
+        raw_on_chain_commitment_key: &str,
+    ) -> JsResult<HexString> {
+        credential_id_aux(
+            &self.0,
+            identity_provider_index,
+            identity_index,
+            credential_counter,
+            raw_on_chain_commitment_key,
+        )
+        .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getPrfKey)]
+    pub fn get_prf_key(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+    ) -> JsResult<HexString> {
+        prf_key_aux(&self.0, identity_provider_index, identity_index).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getIdCredSec)]
+    pub fn get_id_cred_sec(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+    ) -> JsResult<HexString> {
+        id_cred_sec_aux(&self.0, identity_provider_index, identity_index).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getSignatureBlindingRandomness)]
+    pub fn get_signature_blinding_randomness(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+    ) -> JsResult<HexString> {
+        signature_blinding_randomness_aux(&self.0, identity_provider_index, identity_index)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getAttributeCommitmentRandomness)]
+    pub fn get_attribute_commitment_randomness(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u32,
+        attribute: u8,
+    ) -> JsResult<HexString> {
+        attribute_commitment_randomness_aux(
+            &self.0,
+            identity_provider_index,
+            identity_index,
+            credential_counter,
+            attribute,
+        )
+        .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getVerifiableCredentialSigningKey)]
+    pub fn get_verifiable_credential_signing_key(
+        &self,
+        issuer_index: u64,
+        issuer_subindex: u64,
+        verifiable_credential_index: u32,
+    ) -> JsResult<HexString> {
+        verifiable_credential_signing_key_aux(
+            &self.0,
+            ContractAddress::new(issuer_index, issuer_subindex),
+            verifiable_credential_index,
+        )
+        .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getVerifiableCredentialPublicKey)]
+    pub fn get_verifiable_credential_public_key(
+        &self,
+        issuer_index: u64,
+        issuer_subindex: u64,
+        verifiable_credential_index: u32,
+    ) -> JsResult<HexString> {
+        verifiable_credential_public_key_aux(
+            &self.0,
+            ContractAddress::new(issuer_index, issuer_subindex),
+            verifiable_credential_index,
+        )
+        .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getVerifiableCredentialBackupEncryptionKey)]
+    pub fn get_verifiable_credential_backup_encryption_key(&self) -> JsResult<HexString> {
+        verifiable_credential_backup_encryption_key_aux(&self.0).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = getEncryptionSecretKey)]
+    pub fn get_encryption_secret_key(
+        &self,
+        identity_provider_index: u32,
+        identity_index: u32,
+        credential_counter: u8,
+        raw_global_context: &str,
+    ) -> JsResult<HexString> {
+        encryption_secret_key_aux(
+            &self.0,
+            identity_provider_index,
+            identity_index,
+            credential_counter,
+            raw_global_context,
+        )
+        .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = deriveBakerKeys)]
+    pub fn derive_baker_keys(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        derive_baker_keys_aux(&self.0, input).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = encryptWeb3IdBackup)]
+    pub fn encrypt_web3_id_backup(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        encrypt_web3_id_backup_aux(&self.0, input).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = decryptWeb3IdBackup)]
+    pub fn decrypt_web3_id_backup(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        decrypt_web3_id_backup_aux(&self.0, input).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = deriveCredentials)]
+    pub fn derive_credentials(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        derive_credentials_aux(&self.0, input).map_err(to_js_error)
+    }
+
+    /// Sign a message with the key with the given index of the credential.
+    /// The account address and 8 zero bytes are prepended to the message
+    /// before signing.
+    #[wasm_bindgen(js_name = signMessage)]
+    pub fn sign_message(&self, raw_input: JsonString) -> JsResult<HexString> {
+        let input = serde_json::from_str(&raw_input)?;
+        sign_message_with_credential_key_aux(&self.0, input).map_err(to_js_error)
+    }
+
+    /// Create an identity request, deriving the secrets of the identity from
+    /// the seed of the wallet.
+    #[wasm_bindgen(js_name = createIdRequestV1)]
+    pub fn create_id_request_v1(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        with_id_request_secrets(Some(&self.0), input)
+            .and_then(|input| Ok(serde_json::from_value(input)?))
+            .and_then(create_identity_object_request_v1_aux)
+            .map_err(to_js_error)
+    }
+
+    /// Create an identity recovery request, deriving the secrets of the
+    /// identity from the seed of the wallet.
+    #[wasm_bindgen(js_name = createIdentityRecoveryRequest)]
+    pub fn create_identity_recovery_request(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        with_identity_recovery_secrets(Some(&self.0), input)
+            .and_then(|input| Ok(serde_json::from_value(input)?))
+            .and_then(create_identity_recovery_request_aux)
+            .map_err(to_js_error)
+    }
+
+    /// Create an unsigned credential, deriving the secrets of the identity and
+    /// the attribute randomness from the seed of the wallet.
+    #[wasm_bindgen(js_name = createUnsignedCredentialV1)]
+    pub fn create_unsigned_credential_v1(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        with_unsigned_credential_secrets(Some(&self.0), input)
+            .and_then(|input| Ok(serde_json::from_value(input)?))
+            .and_then(create_unsigned_credential_v1_aux)
+            .map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = createCredentialV1)]
+    pub fn create_credential_v1(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        create_credential_v1_aux(&self.0, input).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = createExistingAccountCredentialV1)]
+    pub fn create_existing_account_credential_v1(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        create_existing_account_credential_v1_aux(&self.0, input).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = createIdProof)]
+    pub fn create_id_proof(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        create_id_proof_aux(&self.0, input).map_err(to_js_error)
+    }
+
+    /// Verify an identity object of the wallet, deriving the secrets of the
+    /// identity from the seed of the wallet to also verify the signature of
+    /// the identity provider.
+    #[wasm_bindgen(js_name = verifyIdentityObject)]
+    pub fn verify_identity_object(&self, raw_input: JsonString) -> JsResult {
+        let input = serde_json::from_str(&raw_input)?;
+        with_id_request_secrets(Some(&self.0), input)
+            .and_then(|input| Ok(serde_json::from_value(input)?))
+            .and_then(verify_identity_object_aux)
+            .map_err(to_js_error)
+    }
+}
+
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct VerifyPresentationInput {
@@ -355,6 +848,24 @@ pub fn create_presentation_v1(raw_input: JsonString) -> JsResult {
         .map_err(to_js_error)
 }
 
+#[wasm_bindgen(js_name = decodeIdentityAttributes)]
+pub fn decode_identity_attributes_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    decode_identity_attributes_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = validateStatements)]
+pub fn validate_statements_ext(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    validate_statements_aux(input).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = checkPresentationSatisfiabilityV1)]
+pub fn check_presentation_satisfiability_v1(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    check_presentation_satisfiability_aux(input).map_err(to_js_error)
+}
+
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct VerificationV1Input {
@@ -379,6 +890,12 @@ pub fn verify_presentation_v1(raw_input: JsonString) -> JsResult {
         .map_err(to_js_error)
 }
 
+#[wasm_bindgen(js_name = verifyPresentationV1WithPolicy)]
+pub fn verify_presentation_v1_with_policy(raw_input: JsonString) -> JsResult {
+    let input = serde_json::from_str(&raw_input)?;
+    verify_presentation_policy_aux(input).map_err(to_js_error)
+}
+
 #[derive(Clone)]
 struct PublicInfo(HashMap<String, cbor::value::Value>);
 
@@ -387,13 +904,26 @@ impl<'de> serde::Deserialize<'de> for PublicInfo {
     where
         D: serde::Deserializer<'de>,
     {
-        let string_map: HashMap<String, String> = HashMap::deserialize(deserializer)?;
-        let mapped: Result<HashMap<String, cbor::value::Value>, D::Error> = string_map
+        // Strings are hex encoded CBOR values, while JSON values to convert
+        // to CBOR are given as `{"$json": <value>}`.
+        let json_map: HashMap<String, serde_json::Value> = HashMap::deserialize(deserializer)?;
+        let mapped: Result<HashMap<String, cbor::value::Value>, D::Error> = json_map
             .into_iter()
             .map(|(k, v)| {
-                let bytes = hex::decode(&v).map_err(de::Error::custom)?;
-                let value: cbor::value::Value =
-                    cbor::cbor_decode(&bytes).map_err(de::Error::custom)?;
+                let value = match v {
+                    serde_json::Value::String(v) => {
+                        let bytes = hex::decode(&v).map_err(de::Error::custom)?;
+                        cbor::cbor_decode(&bytes).map_err(de::Error::custom)?
+                    }
+                    serde_json::Value::Object(v) if v.len() == 1 && v.contains_key("$json") => {
+                        json_to_cbor_value(&v["$json"]).map_err(de::Error::custom)?
+                    }
+                    _ => {
+                        return Err(de::Error::custom(
+                            "Expected a hex encoded CBOR value or {\"$json\": <value>}",
+                        ))
+                    }
+                };
                 Ok((k, value))
             })
             .collect();
@@ -464,3 +994,649 @@ pub fn compute_verification_audit_v1_anchor_hash(raw_input: JsonString) -> JsRes
     let anchor = input.record.to_anchor(public.map(|p| p.0));
     Ok(anchor.hash.bytes.to_vec())
 }
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildVerificationAuditV1Input {
+    /// The verifier chosen id of the audit record.
+    id: String,
+    /// The verification request the presentation was made for.
+    request: VerificationRequest,
+    /// The verified presentation.
+    presentation: VerifiablePresentationV1,
+    /// The optional public info to register with the anchor.
+    public_info: Option<PublicInfo>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BuiltVerificationAuditV1 {
+    record: VerificationAuditRecord,
+    /// The hex encoded CBOR anchor of the record.
+    anchor: HexString,
+    anchor_hash: HexString,
+}
+
+/// Builds a verification audit v1 record from a verification request, the
+/// verified presentation and an id, together with its anchor and the hash of
+/// the anchor. Fails if the presentation does not answer the request.
+#[wasm_bindgen(js_name = buildVerificationAuditV1)]
+pub fn build_verification_audit_v1(raw_input: JsonString) -> JsResult {
+    let input: BuildVerificationAuditV1Input = serde_json::from_str(&raw_input)?;
+    check_presentation_answers_request(&input.request, &input.presentation).map_err(to_js_error)?;
+    let record = VerificationAuditRecord::new(input.id, input.request, input.presentation);
+    let anchor = record.to_anchor(input.public_info.map(|p| p.0));
+    let built = BuiltVerificationAuditV1 {
+        anchor: hex::encode(cbor_encode(&anchor).map_err(to_js_error)?),
+        anchor_hash: hex::encode(anchor.hash.bytes),
+        record,
+    };
+    Ok(serde_json::to_string(&built)?)
+}
+
+/// The decoded content of a verification request or audit anchor. The public
+/// info is given as hex encoded CBOR strings, which is a form the anchor
+/// inputs take, so it can be passed back when verifying the anchor.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodedAnchor {
+    hash: HexString,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_info: Option<HashMap<String, HexString>>,
+}
+
+impl DecodedAnchor {
+    fn new(
+        hash: base::hashes::Hash,
+        public: Option<HashMap<String, cbor::value::Value>>,
+    ) -> anyhow::Result<Self> {
+        let public_info = public
+            .map(|public| {
+                public
+                    .into_iter()
+                    .map(|(k, v)| Ok((k, hex::encode(cbor_encode(&v)?))))
+                    .collect::<anyhow::Result<_>>()
+            })
+            .transpose()?;
+        Ok(Self {
+            hash: hex::encode(hash.bytes),
+            public_info,
+        })
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodeAnchorInput {
+    /// The hex encoded CBOR anchor, e.g. as registered on chain.
+    anchor: HexString,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyVerificationRequestV1AnchorInput {
+    /// The hex encoded CBOR anchor, e.g. as registered on chain.
+    anchor: HexString,
+    /// The verification request to check against the anchor.
+    request: VerificationRequestV1Input,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyVerificationAuditV1AnchorInput {
+    /// The hex encoded CBOR anchor, e.g. as registered on chain.
+    anchor: HexString,
+    /// The verification audit record to check against the anchor.
+    record: VerificationAuditRecord,
+    /// The public info the anchor must have. If not given, the public info
+    /// of the anchor is not checked.
+    public_info: Option<PublicInfo>,
+}
+
+/// Decodes a CBOR encoded verification request v1 anchor into JSON containing
+/// the hash of the request and the public info.
+#[wasm_bindgen(js_name = decodeVerificationRequestV1Anchor)]
+pub fn decode_verification_request_v1_anchor(raw_input: JsonString) -> JsResult {
+    let input: DecodeAnchorInput = serde_json::from_str(&raw_input)?;
+    let anchor: VerificationRequestAnchor = cbor::cbor_decode(&hex::decode(&input.anchor)?)
+        .context("Invalid verification request anchor")
+        .map_err(to_js_error)?;
+    let decoded = DecodedAnchor::new(anchor.hash, anchor.public).map_err(to_js_error)?;
+    Ok(serde_json::to_string(&decoded)?)
+}
+
+fn verify_request_anchor(input: VerifyVerificationRequestV1AnchorInput) -> anyhow::Result<bool> {
+    let anchor: VerificationRequestAnchor = cbor::cbor_decode(&hex::decode(&input.anchor)?)
+        .context("Invalid verification request anchor")?;
+    let public = input.request.public_info.clone();
+    let mut expected = VerificationRequestData::from(input.request).to_anchor(public.map(|p| p.0));
+    // The public info is only checked if it is given.
+    if expected.public.is_none() {
+        expected.public = anchor.public.clone();
+    }
+    Ok(anchor == expected)
+}
+
+/// Checks that a CBOR encoded verification request v1 anchor is the anchor
+/// of the given verification request, i.e. that it has the type, version and
+/// hash of the anchor of the request, and the public info of the request if
+/// it has any.
+#[wasm_bindgen(js_name = verifyVerificationRequestV1Anchor)]
+pub fn verify_verification_request_v1_anchor(raw_input: JsonString) -> JsResult<bool> {
+    let input: VerifyVerificationRequestV1AnchorInput = serde_json::from_str(&raw_input)?;
+    verify_request_anchor(input).map_err(to_js_error)
+}
+
+/// Decodes a CBOR encoded verification audit v1 anchor into JSON containing
+/// the hash of the audit record and the public info.
+#[wasm_bindgen(js_name = decodeVerificationAuditV1Anchor)]
+pub fn decode_verification_audit_v1_anchor(raw_input: JsonString) -> JsResult {
+    let input: DecodeAnchorInput = serde_json::from_str(&raw_input)?;
+    let anchor: VerificationAuditAnchor = cbor::cbor_decode(&hex::decode(&input.anchor)?)
+        .context("Invalid verification audit anchor")
+        .map_err(to_js_error)?;
+    let decoded = DecodedAnchor::new(anchor.hash, anchor.public).map_err(to_js_error)?;
+    Ok(serde_json::to_string(&decoded)?)
+}
+
+fn verify_audit_anchor(input: VerifyVerificationAuditV1AnchorInput) -> anyhow::Result<bool> {
+    let anchor: VerificationAuditAnchor = cbor::cbor_decode(&hex::decode(&input.anchor)?)
+        .context("Invalid verification audit anchor")?;
+    let mut expected = input.record.to_anchor(input.public_info.map(|p| p.0));
+    // The public info is only checked if it is given.
+    if expected.public.is_none() {
+        expected.public = anchor.public.clone();
+    }
+    Ok(anchor == expected)
+}
+
+/// Checks that a CBOR encoded verification audit v1 anchor is the anchor of
+/// the given audit record, i.e. that it has the type, version and hash of the
+/// anchor of the record, and the given public info if any.
+#[wasm_bindgen(js_name = verifyVerificationAuditV1Anchor)]
+pub fn verify_verification_audit_v1_anchor(raw_input: JsonString) -> JsResult<bool> {
+    let input: VerifyVerificationAuditV1AnchorInput = serde_json::from_str(&raw_input)?;
+    verify_audit_anchor(input).map_err(to_js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use concordium_base::id::test::test_create_ip_info;
+    use rand::thread_rng;
+    use serde_json::json;
+
+    trait JsUnwrap<T> {
+        fn js_unwrap(self) -> T;
+    }
+
+    /// `JsError` does not implement `Debug`, so `unwrap` cannot be used.
+    impl<T> JsUnwrap<T> for JsResult<T> {
+        fn js_unwrap(self) -> T {
+            self.unwrap_or_else(|_| panic!("Unexpected error"))
+        }
+    }
+
+    /// A verification request for a date of birth range proof from an
+    /// account credential, and a presentation answering it.
+    fn audit_request_and_presentation() -> (VerificationRequest, VerifiablePresentationV1) {
+        use concordium_base::{
+            base::CredentialRegistrationID,
+            curve_arithmetic::Curve,
+            hashes::{BlockHash, TransactionHash},
+            id::{
+                constants::AttributeKind,
+                id_proof_types::AttributeInRangeStatement,
+                pedersen_commitment::Value,
+                types::{Attribute, AttributeTag, IpIdentity},
+            },
+            web3id::{
+                did::Network,
+                v1::{
+                    anchor::{
+                        ContextLabel, IdentityCredentialType, IdentityProviderDid,
+                        LabeledContextProperty, Nonce, RequestedIdentitySubjectClaims,
+                        RequestedStatement, UnfilledContextInformationBuilder,
+                    },
+                    AccountBasedSubjectClaims, AtomicStatementV1, ContextInformation,
+                    OwnedAccountCredentialProofPrivateInputs, OwnedCredentialProofPrivateInputs,
+                    RequestV1, SubjectClaims,
+                },
+            },
+        };
+
+        let mut rng = thread_rng();
+        let global = GlobalContext::<ArCurve>::generate("Test".into());
+        let text = |s: &str| Web3IdAttribute::String(AttributeKind::try_new(s.into()).unwrap());
+        let dob = text("19950101");
+        let (_, randomness) = global
+            .on_chain_commitment_key
+            .commit(&Value::<ArCurve>::new(dob.to_field_element()), &mut rng);
+        let inputs =
+            OwnedCredentialProofPrivateInputs::<IpPairing, ArCurve, Web3IdAttribute>::Account(
+                OwnedAccountCredentialProofPrivateInputs {
+                    issuer: IpIdentity(0),
+                    attribute_values: [(AttributeTag(3), dob)].into(),
+                    attribute_randomness: [(AttributeTag(3), randomness)].into(),
+                },
+            );
+        let statement = AttributeInRangeStatement {
+            attribute_tag: AttributeTag(3),
+            lower: text("18000101"),
+            upper: text("20000101"),
+            _phantom: Default::default(),
+        };
+
+        let context = UnfilledContextInformationBuilder::new_simple(
+            Nonce([1; 32]),
+            "connection".into(),
+            "resource".into(),
+        )
+        .build();
+        let request = VerificationRequest {
+            subject_claims: vec![RequestedSubjectClaims::Identity(
+                RequestedIdentitySubjectClaims {
+                    statements: vec![RequestedStatement::AttributeInRange(statement.clone())],
+                    issuers: vec![IdentityProviderDid::new(0, Network::Testnet)],
+                    source: vec![IdentityCredentialType::AccountCredential],
+                },
+            )],
+            anchor_transaction_hash: TransactionHash::new([2; 32]),
+            context: context.clone(),
+        };
+        let presentation_context = ContextInformation {
+            given: context
+                .given
+                .iter()
+                .map(LabeledContextProperty::to_context_property)
+                .collect(),
+            requested: context
+                .requested
+                .iter()
+                .map(|label| {
+                    assert_eq!(*label, ContextLabel::BlockHash);
+                    LabeledContextProperty::BlockHash(BlockHash::new([3; 32])).to_context_property()
+                })
+                .collect(),
+        };
+        let presentation = RequestV1 {
+            context: presentation_context,
+            subject_claims: vec![SubjectClaims::Account(AccountBasedSubjectClaims {
+                network: Network::Testnet,
+                issuer: IpIdentity(0),
+                cred_id: CredentialRegistrationID::new(ArCurve::generate(&mut rng)),
+                statements: vec![AtomicStatementV1::AttributeInRange(statement)],
+            })],
+        }
+        .prove(&global, [inputs.borrow()].into_iter())
+        .unwrap();
+        (request, presentation)
+    }
+
+    #[test]
+    fn build_verification_audit_v1_round_trip() {
+        let (request, presentation) = audit_request_and_presentation();
+        let built = build_verification_audit_v1(
+            json!({
+                "id": "audit-1",
+                "request": request,
+                "presentation": presentation,
+                "publicInfo": { "key": { "$json": 4 } },
+            })
+            .to_string(),
+        )
+        .js_unwrap();
+        let built: serde_json::Value = serde_json::from_str(&built).unwrap();
+        assert_eq!(
+            built["record"]["type"],
+            json!("ConcordiumVerificationAuditRecord")
+        );
+        assert_eq!(built["record"]["version"], json!(1));
+        assert_eq!(built["record"]["id"], json!("audit-1"));
+
+        let decoded: serde_json::Value = serde_json::from_str(
+            &decode_verification_audit_v1_anchor(json!({ "anchor": built["anchor"] }).to_string())
+                .js_unwrap(),
+        )
+        .unwrap();
+        assert_eq!(decoded["hash"], built["anchorHash"]);
+        assert_eq!(decoded["publicInfo"], json!({ "key": "04" }));
+        assert!(verify_verification_audit_v1_anchor(
+            json!({ "anchor": built["anchor"], "record": built["record"] }).to_string()
+        )
+        .js_unwrap());
+
+        // A presentation which does not answer the request is not audited.
+        // The check is made on the errors before they are converted to
+        // `JsError`, which cannot be created outside of wasm.
+        assert!(check_presentation_answers_request(&request, &presentation).is_ok());
+        let mut other_request = request.clone();
+        let RequestedSubjectClaims::Identity(claims) = &mut other_request.subject_claims[0];
+        claims.statements.clear();
+        assert!(check_presentation_answers_request(&other_request, &presentation).is_err());
+        let mut other_request = request.clone();
+        other_request.context.given.pop();
+        assert!(check_presentation_answers_request(&other_request, &presentation).is_err());
+        let mut other_request = request;
+        other_request.subject_claims.clear();
+        assert!(check_presentation_answers_request(&other_request, &presentation).is_err());
+    }
+
+    #[test]
+    fn verify_verification_request_v1_anchor_checks_the_full_anchor() {
+        let (request, _) = audit_request_and_presentation();
+        let input = json!({
+            "context": request.context,
+            "subjectClaims": request.subject_claims,
+            "publicInfo": { "key": { "$json": 4 } },
+        });
+        let anchor = create_verification_request_v1_anchor(input.to_string()).js_unwrap();
+        let verify = |anchor: &[u8], request: &serde_json::Value| {
+            verify_request_anchor(
+                serde_json::from_value(
+                    json!({ "anchor": hex::encode(anchor), "request": request }),
+                )
+                .unwrap(),
+            )
+        };
+        assert!(verify(&anchor, &input).unwrap());
+        assert!(verify_verification_request_v1_anchor(
+            json!({ "anchor": hex::encode(&anchor), "request": input }).to_string()
+        )
+        .js_unwrap());
+
+        // Without public info, only the type, version and hash are checked.
+        let mut without_public_info = input.clone();
+        without_public_info
+            .as_object_mut()
+            .unwrap()
+            .remove("publicInfo");
+        assert!(verify(&anchor, &without_public_info).unwrap());
+        let mut other_public_info = input.clone();
+        other_public_info["publicInfo"] = json!({ "key": "05" });
+        assert!(!verify(&anchor, &other_public_info).unwrap());
+
+        // A different request has a different hash.
+        let mut other_request = input.clone();
+        other_request["subjectClaims"] = json!([]);
+        assert!(!verify(&anchor, &other_request).unwrap());
+
+        let decoded: VerificationRequestAnchor = cbor::cbor_decode(&anchor).unwrap();
+        let mut wrong_type = decoded.clone();
+        wrong_type.r#type = "CCDVAA".into();
+        assert!(!verify(&cbor_encode(&wrong_type).unwrap(), &input).unwrap());
+        let mut wrong_version = decoded;
+        wrong_version.version = 2;
+        assert!(!verify(&cbor_encode(&wrong_version).unwrap(), &input).unwrap());
+
+        assert!(verify(&[0xa1, 0x00], &input).is_err());
+        assert!(verify(&anchor[..anchor.len() - 1], &input).is_err());
+    }
+
+    #[test]
+    fn decoded_public_info_verifies_the_anchor() {
+        let (request, presentation) = audit_request_and_presentation();
+        let public_info = json!({
+            "key": { "$json": 4 },
+            "nested": { "$json": { "list": [1.5, "a"], "bytes": { "$bytes": "0102" } } },
+        });
+        let input = json!({
+            "context": request.context,
+            "subjectClaims": request.subject_claims,
+            "publicInfo": public_info,
+        });
+        let anchor = create_verification_request_v1_anchor(input.to_string()).js_unwrap();
+        let decoded: serde_json::Value = serde_json::from_str(
+            &decode_verification_request_v1_anchor(
+                json!({ "anchor": hex::encode(&anchor) }).to_string(),
+            )
+            .js_unwrap(),
+        )
+        .unwrap();
+        let mut request_input = input.clone();
+        request_input["publicInfo"] = decoded["publicInfo"].clone();
+        assert!(verify_verification_request_v1_anchor(
+            json!({ "anchor": hex::encode(&anchor), "request": request_input }).to_string()
+        )
+        .js_unwrap());
+
+        let record = VerificationAuditRecord::new("audit-1".into(), request, presentation);
+        let anchor = create_verification_audit_v1_anchor(
+            json!({ "record": record, "publicInfo": public_info }).to_string(),
+        )
+        .js_unwrap();
+        let decoded: serde_json::Value = serde_json::from_str(
+            &decode_verification_audit_v1_anchor(
+                json!({ "anchor": hex::encode(&anchor) }).to_string(),
+            )
+            .js_unwrap(),
+        )
+        .unwrap();
+        assert!(verify_verification_audit_v1_anchor(
+            json!({
+                "anchor": hex::encode(&anchor),
+                "record": record,
+                "publicInfo": decoded["publicInfo"],
+            })
+            .to_string()
+        )
+        .js_unwrap());
+    }
+
+    #[test]
+    fn hex_encoded_public_info_is_read_as_cbor() {
+        let mut input = json!({
+            "context": {
+                "type": "ConcordiumUnfilledContextInformationV1",
+                "given": [
+                    { "label": "Nonce", "context": hex::encode([1; 32]) },
+                    { "label": "ConnectionID", "context": "connection" },
+                    { "label": "ResourceID", "context": "resource" },
+                ],
+                "requested": ["BlockHash"],
+            },
+            "subjectClaims": [{
+                "type": "identity",
+                "issuers": ["did:ccd:testnet:idp:0"],
+                "source": ["accountCredential"],
+                "statements": [{
+                    "type": "AttributeInRange",
+                    "attributeTag": "dob",
+                    "lower": "18000101",
+                    "upper": "20000101",
+                }],
+            }],
+            "publicInfo": { "key": "04", "name": "6474657374", "bytes": "420102" },
+        });
+        // The anchor created for hex encoded CBOR public info before JSON
+        // values were supported.
+        let expected = "a464686173685820a552094f873c1e8fb0b87cc452eb3cf599c1a38aa11187db5ae7ef02662416de647479706566434344565241667075626c6963a3636b657904646e616d6564746573746562797465734201026776657273696f6e01";
+        let anchor = create_verification_request_v1_anchor(input.to_string()).js_unwrap();
+        assert_eq!(hex::encode(anchor), expected);
+
+        // The same public info given as JSON values.
+        input["publicInfo"] = json!({
+            "key": { "$json": 4 },
+            "name": { "$json": "test" },
+            "bytes": { "$json": { "$bytes": "0102" } },
+        });
+        let anchor = create_verification_request_v1_anchor(input.to_string()).js_unwrap();
+        assert_eq!(hex::encode(anchor), expected);
+
+        let parse = |public_info| serde_json::from_value::<PublicInfo>(public_info);
+        assert!(parse(json!({ "key": "test" })).is_err());
+        assert!(parse(json!({ "key": "1a00" })).is_err());
+        assert!(parse(json!({ "key": 4 })).is_err());
+        assert!(parse(json!({ "key": { "$json": 4, "other": 5 } })).is_err());
+    }
+
+    #[test]
+    fn verify_verification_audit_v1_anchor_checks_the_full_anchor() {
+        let (request, presentation) = audit_request_and_presentation();
+        let record = VerificationAuditRecord::new("audit-1".into(), request, presentation);
+        let anchor = create_verification_audit_v1_anchor(
+            json!({ "record": record, "publicInfo": { "key": "04" } }).to_string(),
+        )
+        .js_unwrap();
+        let verify = |anchor: &[u8], record: &VerificationAuditRecord, public_info| {
+            verify_audit_anchor(
+                serde_json::from_value(json!({
+                    "anchor": hex::encode(anchor),
+                    "record": record,
+                    "publicInfo": public_info,
+                }))
+                .unwrap(),
+            )
+        };
+        assert!(verify(&anchor, &record, json!({ "key": { "$json": 4 } })).unwrap());
+        assert!(verify(&anchor, &record, json!(null)).unwrap());
+        assert!(!verify(&anchor, &record, json!({ "key": "05" })).unwrap());
+
+        // A different record has a different hash.
+        let mut other_record = record.clone();
+        other_record.id = "audit-2".into();
+        assert!(!verify(&anchor, &other_record, json!(null)).unwrap());
+
+        let decoded: VerificationAuditAnchor = cbor::cbor_decode(&anchor).unwrap();
+        let mut wrong_type = decoded.clone();
+        wrong_type.r#type = "CCDVRA".into();
+        assert!(!verify(&cbor_encode(&wrong_type).unwrap(), &record, json!(null)).unwrap());
+        let mut wrong_version = decoded;
+        wrong_version.version = 2;
+        assert!(!verify(&cbor_encode(&wrong_version).unwrap(), &record, json!(null)).unwrap());
+
+        assert!(verify(&[0xa1, 0x00], &record, json!(null)).is_err());
+        assert!(verify(&anchor[..anchor.len() - 1], &record, json!(null)).is_err());
+    }
+
+    const TEST_SEED: &str = "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4";
+
+    #[test]
+    fn hd_wallet_matches_seed_functions() {
+        let net = "Testnet";
+        let seed = || TEST_SEED.to_string();
+        let wallet = HdWallet::new(hex::decode(TEST_SEED).unwrap(), net).js_unwrap();
+        let global_context = GlobalContext::<ArCurve>::generate("genesis".into());
+        let commitment_key = serde_json::to_string(&global_context.on_chain_commitment_key)
+            .unwrap()
+            .trim_matches('"')
+            .to_string();
+        let global_context = serde_json::to_string(&global_context).unwrap();
+
+        assert_eq!(
+            wallet.get_account_signing_key(1, 2, 3).js_unwrap(),
+            get_account_signing_key_ext(seed(), net, 1, 2, 3).js_unwrap()
+        );
+        assert_eq!(
+            wallet.get_account_public_key(1, 2, 3).js_unwrap(),
+            get_account_public_key_ext(seed(), net, 1, 2, 3).js_unwrap()
+        );
+        assert_eq!(
+            wallet
+                .get_credential_id(1, 2, 3, &commitment_key)
+                .js_unwrap(),
+            get_credential_id_ext(seed(), net, 1, 2, 3, &commitment_key).js_unwrap()
+        );
+        assert_eq!(
+            wallet.get_prf_key(1, 2).js_unwrap(),
+            get_prf_key_ext(seed(), net, 1, 2).js_unwrap()
+        );
+        assert_eq!(
+            wallet.get_id_cred_sec(1, 2).js_unwrap(),
+            get_id_cred_sec_ext(seed(), net, 1, 2).js_unwrap()
+        );
+        assert_eq!(
+            wallet.get_signature_blinding_randomness(1, 2).js_unwrap(),
+            get_signature_blinding_randomness_ext(seed(), net, 1, 2).js_unwrap()
+        );
+        assert_eq!(
+            wallet
+                .get_attribute_commitment_randomness(1, 2, 3, 4)
+                .js_unwrap(),
+            get_attribute_commitment_randomness_ext(seed(), net, 1, 2, 3, 4).js_unwrap()
+        );
+        assert_eq!(
+            wallet
+                .get_verifiable_credential_signing_key(5, 6, 7)
+                .js_unwrap(),
+            get_verifiable_credential_signing_key_ext(seed(), net, 5, 6, 7).js_unwrap()
+        );
+        assert_eq!(
+            wallet
+                .get_verifiable_credential_public_key(5, 6, 7)
+                .js_unwrap(),
+            get_verifiable_credential_public_key_ext(seed(), net, 5, 6, 7).js_unwrap()
+        );
+        assert_eq!(
+            wallet
+                .get_verifiable_credential_backup_encryption_key()
+                .js_unwrap(),
+            get_verifiable_credential_backup_encryption_key_ext(seed(), net).js_unwrap()
+        );
+        assert_eq!(
+            wallet
+                .get_encryption_secret_key(1, 2, 3, &global_context)
+                .js_unwrap(),
+            get_encryption_secret_key_ext(seed(), net, 1, 2, 3, &global_context).js_unwrap()
+        );
+
+        // Ed25519 signatures are deterministic, so signing with the wallet and
+        // with the derived key gives the same signature.
+        let address = "3eP94feEdmhYiPC1333F9VoV31KGMswonuHk5tqmZrzf761zK5";
+        let message = hex::encode("test");
+        let signature = wallet
+            .sign_message(
+                json!({
+                    "identityProviderIndex": 1,
+                    "identityIndex": 2,
+                    "credentialCounter": 3,
+                    "keyIndex": 0,
+                    "address": address,
+                    "message": message,
+                })
+                .to_string(),
+            )
+            .js_unwrap();
+        let keys = json!({
+            "keys": { "0": { "keys": { "0": {
+                "signKey": wallet.get_account_signing_key(1, 2, 3).js_unwrap(),
+                "verifyKey": wallet.get_account_public_key(1, 2, 3).js_unwrap(),
+            } }, "threshold": 1 } },
+            "threshold": 1,
+        });
+        let signatures: serde_json::Value = serde_json::from_str(
+            &sign_message_ext(
+                json!({ "address": address, "message": message, "keys": keys }).to_string(),
+            )
+            .js_unwrap(),
+        )
+        .unwrap();
+        assert_eq!(signatures, json!({ "0": { "0": signature } }));
+    }
+
+    #[test]
+    fn hd_wallet_identity_secrets_match_seed_input() {
+        let wallet = HdWallet::new(hex::decode(TEST_SEED).unwrap(), "Testnet").js_unwrap();
+        let ip_info = test_create_ip_info(&mut thread_rng(), 1, 10).public_ip_info;
+        let input = json!({ "ipInfo": ip_info, "identityIndex": 2 });
+        let mut seed_input = input.clone();
+        seed_input["seedAsHex"] = json!(TEST_SEED);
+        seed_input["net"] = json!("Testnet");
+
+        let from_wallet = with_id_request_secrets(Some(&wallet.0), input.clone()).unwrap();
+        assert_eq!(
+            from_wallet,
+            with_id_request_secrets(None, seed_input.clone()).unwrap()
+        );
+        assert_eq!(
+            from_wallet["idCredSec"],
+            json!(wallet.get_id_cred_sec(0, 2).js_unwrap())
+        );
+        assert_eq!(
+            with_identity_recovery_secrets(Some(&wallet.0), input.clone()).unwrap(),
+            with_identity_recovery_secrets(None, seed_input).unwrap()
+        );
+        // Without a wallet or a seed, the input is left as is.
+        assert_eq!(with_id_request_secrets(None, input.clone()).unwrap(), input);
+    }
+}